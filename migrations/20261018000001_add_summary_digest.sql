@@ -0,0 +1,4 @@
+-- Store the structured digest (JSON) that a summary was rendered from.
+-- NULL for summaries produced by the free-form Markdown fallback.
+ALTER TABLE summaries
+ADD COLUMN digest_json JSONB;
@@ -0,0 +1,4 @@
+-- Store the lint results (rule violations before/after the repair request)
+-- for each generated summary. NULL for summaries saved without linting.
+ALTER TABLE summaries
+ADD COLUMN lint_report_json JSONB;
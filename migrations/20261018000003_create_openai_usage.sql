@@ -0,0 +1,13 @@
+-- Per-call OpenAI token usage and estimated cost
+CREATE TABLE IF NOT EXISTS openai_usage (
+    id BIGSERIAL PRIMARY KEY,
+    purpose TEXT NOT NULL,
+    model TEXT NOT NULL,
+    prompt_tokens INTEGER NOT NULL,
+    completion_tokens INTEGER NOT NULL,
+    cost_usd DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for daily/monthly totals
+CREATE INDEX IF NOT EXISTS idx_openai_usage_created_at ON openai_usage(created_at DESC);
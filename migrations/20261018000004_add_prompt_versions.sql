@@ -0,0 +1,7 @@
+-- Record which prompt templates produced each summary and translation
+-- (content hash from the prompt library). NULL for rows saved before versioning.
+ALTER TABLE summaries
+ADD COLUMN prompt_version TEXT;
+
+ALTER TABLE summary_translations
+ADD COLUMN prompt_version TEXT;
@@ -0,0 +1,24 @@
+-- Key summaries and delivery failures by digest, and let subscribers choose
+-- which digests they receive. Everything before this belongs to 'default'.
+ALTER TABLE summaries
+ADD COLUMN digest_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_summaries_digest_created_at ON summaries(digest_id, created_at DESC);
+
+-- NULL for failures not tied to a digest (e.g. broadcasts)
+ALTER TABLE delivery_failures
+ADD COLUMN digest_id TEXT;
+
+CREATE TABLE IF NOT EXISTS digest_subscriptions (
+    chat_id BIGINT NOT NULL REFERENCES subscribers(chat_id) ON DELETE CASCADE,
+    digest_id TEXT NOT NULL,
+    subscribed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (chat_id, digest_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_digest_subscriptions_digest_id ON digest_subscriptions(digest_id);
+
+-- Existing subscribers keep receiving the original digest
+INSERT INTO digest_subscriptions (chat_id, digest_id, subscribed_at)
+SELECT chat_id, 'default', subscribed_at FROM subscribers WHERE is_active = TRUE
+ON CONFLICT DO NOTHING;
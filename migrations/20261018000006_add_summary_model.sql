@@ -0,0 +1,4 @@
+-- Record which model wrote each summary (the primary or an OPENAI_FALLBACK_MODELS
+-- entry). NULL for rows saved before fallbacks existed.
+ALTER TABLE summaries
+ADD COLUMN model TEXT;
@@ -0,0 +1,24 @@
+-- Developing stories tracked across digests. Items outlive the summaries they
+-- came from (summaries are pruned to the last 10 per digest), so they keep
+-- their own headline and URL and reference the summary without a foreign key.
+CREATE TABLE IF NOT EXISTS stories (
+    id BIGSERIAL PRIMARY KEY,
+    digest_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_stories_digest_last_seen ON stories(digest_id, last_seen_at DESC);
+
+CREATE TABLE IF NOT EXISTS story_items (
+    id BIGSERIAL PRIMARY KEY,
+    story_id BIGINT NOT NULL REFERENCES stories(id) ON DELETE CASCADE,
+    summary_id BIGINT NOT NULL,
+    headline TEXT NOT NULL,
+    url TEXT NOT NULL,
+    entities TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_story_items_story_id ON story_items(story_id, created_at);
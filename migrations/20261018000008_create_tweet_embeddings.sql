@@ -0,0 +1,12 @@
+-- Tweet embeddings for the optional embeddings stage (EMBEDDING_MODEL), cached
+-- so overlapping fetch windows don't embed the same tweet twice
+CREATE TABLE IF NOT EXISTS tweet_embeddings (
+    tweet_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    embedding REAL[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tweet_id, model)
+);
+
+-- Index for cleanup of old embeddings
+CREATE INDEX IF NOT EXISTS idx_tweet_embeddings_created_at ON tweet_embeddings(created_at);
@@ -0,0 +1,12 @@
+-- Interests declared with /interests (sections, keywords, accounts and whether
+-- to show only matching items), stored as JSON. No row means the full digest.
+CREATE TABLE IF NOT EXISTS subscriber_interests (
+    chat_id BIGINT PRIMARY KEY REFERENCES subscribers(chat_id) ON DELETE CASCADE,
+    interests JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Translated structured digest, so personalized digests can be rendered from
+-- a cached translation. NULL for free-form translations.
+ALTER TABLE summary_translations
+ADD COLUMN digest_json JSONB;
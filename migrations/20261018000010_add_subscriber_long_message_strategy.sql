@@ -0,0 +1,4 @@
+-- How over-long digests are delivered to a subscriber ("split", "condense" or
+-- "truncate"), chosen with /long. NULL uses LONG_MESSAGE_STRATEGY.
+ALTER TABLE subscribers
+ADD COLUMN long_message_strategy TEXT;
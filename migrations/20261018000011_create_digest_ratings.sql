@@ -0,0 +1,20 @@
+-- Reader ratings of delivered digests (1-5, from the buttons under each
+-- digest), one per subscriber and summary; pressing another button changes
+-- the rating. `comment` is the optional free-text follow-up.
+--
+-- The digest, model and prompt version are copied from the summary, since
+-- summaries are pruned long before their ratings stop being useful.
+CREATE TABLE IF NOT EXISTS digest_ratings (
+    summary_id BIGINT NOT NULL,
+    chat_id BIGINT NOT NULL,
+    digest_id TEXT NOT NULL,
+    model TEXT,
+    prompt_version TEXT,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    comment TEXT,
+    rated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (summary_id, chat_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_digest_ratings_chat_rated_at ON digest_ratings(chat_id, rated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_digest_ratings_rated_at ON digest_ratings(rated_at);
@@ -0,0 +1,25 @@
+-- Delivery ledger: one row per summary and chat it is sent to.
+--
+-- Rows are created as 'pending' before a fan-out starts. A chat is claimed
+-- ('sending', attempts + 1) just before its first message is sent, and ends
+-- as 'sent' (with the id of the last message) or 'failed' when Telegram
+-- refused the first message. Failures after a message may have arrived keep
+-- 'sending' with the error, so only 'pending' and 'failed' chats are ever
+-- sent to again.
+CREATE TABLE IF NOT EXISTS deliveries (
+    summary_id BIGINT NOT NULL REFERENCES summaries(id) ON DELETE CASCADE,
+    chat_id BIGINT NOT NULL,
+    digest_id TEXT NOT NULL,
+    language_code VARCHAR(10) NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sending', 'sent', 'failed')),
+    message_id BIGINT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    sent_at TIMESTAMPTZ,
+    PRIMARY KEY (summary_id, chat_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_deliveries_summary_status ON deliveries(summary_id, status);
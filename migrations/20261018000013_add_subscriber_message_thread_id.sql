@@ -0,0 +1,4 @@
+-- Forum topic a group's digests are posted in, chosen with /thread. NULL
+-- posts in the main chat (the General topic of a forum).
+ALTER TABLE subscribers
+ADD COLUMN message_thread_id BIGINT;
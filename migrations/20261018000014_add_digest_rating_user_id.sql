@@ -0,0 +1,9 @@
+-- Ratings from groups and channels are kept per member: a rating is keyed by
+-- the user who pressed the button as well as the chat. In private chats the
+-- user is the chat, which is also assumed for the ratings already stored.
+ALTER TABLE digest_ratings ADD COLUMN IF NOT EXISTS user_id BIGINT;
+UPDATE digest_ratings SET user_id = chat_id WHERE user_id IS NULL;
+ALTER TABLE digest_ratings ALTER COLUMN user_id SET NOT NULL;
+
+ALTER TABLE digest_ratings DROP CONSTRAINT IF EXISTS digest_ratings_pkey;
+ALTER TABLE digest_ratings ADD PRIMARY KEY (summary_id, chat_id, user_id);
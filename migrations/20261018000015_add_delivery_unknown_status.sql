@@ -0,0 +1,11 @@
+-- Deliveries whose send may have reached the chat (no response, or a later
+-- part of a split digest failed) end as 'unknown' instead of staying
+-- 'sending'. 'sending' now only means claimed with no outcome recorded, so
+-- a stale claim (the run crashed) can be taken again without resending to
+-- chats that may already have the summary. 'unknown' chats are never sent
+-- to again.
+ALTER TABLE deliveries DROP CONSTRAINT IF EXISTS deliveries_status_check;
+ALTER TABLE deliveries ADD CONSTRAINT deliveries_status_check
+    CHECK (status IN ('pending', 'sending', 'sent', 'failed', 'unknown'));
+
+UPDATE deliveries SET status = 'unknown' WHERE status = 'sending' AND error IS NOT NULL;
@@ -7,7 +7,7 @@
 //!
 //! The run-all command tests these combinations:
 //!   Models: gpt-4o-mini, gpt-5-nano, gpt-5-mini
-//!   Temperatures: 0.3, 0.7, 1.0 (filtered by supports_custom_temperature)
+//!   Temperatures: 0.3, 0.7, 1.0 (only for models that support a custom temperature)
 //!   = 5 total combinations (gpt-4o-mini×3 temps + gpt-5-nano×1 + gpt-5-mini×1)
 
 use anyhow::{Context, Result};
@@ -15,6 +15,7 @@ use chrono::Utc;
 use std::fs;
 use std::path::Path;
 use tracing::info;
+use twitter_news_summary::models::{ModelRegistry, ModelSpec};
 use twitter_news_summary::{openai, rss, twitter::Tweet};
 
 const CACHE_FILE: &str = "run-history/experiment_tweets.json";
@@ -30,20 +31,14 @@ const TEMPERATURES: &[f32] = &[0.3, 0.7, 1.0];
 /// Number of runs per combination (to measure variation)
 const RUNS_PER_COMBO: u32 = 3;
 
-/// Check if a model supports custom temperature values
-/// Some newer models (gpt-5-nano, gpt-5-mini) only support temperature=1
-fn supports_custom_temperature(model: &str) -> bool {
-    !matches!(model, "gpt-5-nano" | "gpt-5-mini")
+/// Capabilities of a model from the built-in registry
+fn model_spec(model: &str) -> ModelSpec {
+    ModelRegistry::builtin().resolve(model)
 }
 
 /// Check if a model is a reasoning model (uses tokens for internal reasoning)
-/// Reasoning models need much higher max_completion_tokens because they use
-/// tokens for both reasoning (hidden) and output (visible)
 fn is_reasoning_model(model: &str) -> bool {
-    matches!(
-        model,
-        "gpt-5-nano" | "gpt-5-mini" | "o1" | "o1-mini" | "o1-preview"
-    )
+    model_spec(model).supports_reasoning_effort
 }
 
 /// A single experiment combination
@@ -57,7 +52,8 @@ impl Combination {
     fn all() -> Vec<Combination> {
         let mut combos = Vec::new();
         for &model in MODELS {
-            if supports_custom_temperature(model) {
+            // Reasoning models (gpt-5-nano, gpt-5-mini) only support temperature=1
+            if model_spec(model).supports_temperature {
                 // Model supports all temperatures
                 for &temp in TEMPERATURES {
                     combos.push(Combination {
@@ -150,15 +146,10 @@ impl ExperimentConfig {
 
     /// Create a config with specific model and temperature (for run-all)
     fn with_combination(&self, combo: &Combination) -> Self {
-        // Reasoning models (gpt-5-nano, gpt-5-mini, o1 series) use tokens for both
-        // internal reasoning and output. They need much higher max_completion_tokens.
         // Non-reasoning models: 2500 tokens is enough for ~800 word summary
-        // Reasoning models: Need 16000+ tokens (hidden reasoning + visible output)
-        let summary_max_tokens = if is_reasoning_model(&combo.model) {
-            16000
-        } else {
-            self.summary_max_tokens
-        };
+        // Reasoning models: raised to 16000+ (hidden reasoning + visible output)
+        let summary_max_tokens =
+            model_spec(&combo.model).completion_tokens(self.summary_max_tokens);
 
         Self {
             openai_api_key: self.openai_api_key.clone(),
@@ -185,13 +176,28 @@ impl ExperimentConfig {
             openai_model: self.openai_model.clone(),
             openai_api_url: self.openai_api_url.clone(),
             openai_temperature: self.openai_temperature,
+            models: twitter_news_summary::models::ModelRegistry::builtin(),
+            openai_prices: twitter_news_summary::usage::PriceTable::default(),
+            openai_monthly_budget_usd: None,
+            openai_fallback_models: Vec::new(),
+            openai_fallback_api_key: None,
             telegram_bot_token: "unused".to_string(),
             telegram_chat_id: "unused".to_string(),
             telegram_webhook_secret: "unused".to_string(),
+            telegram_api_url: std::env::var("TELEGRAM_API_URL")
+                .unwrap_or_else(|_| twitter_news_summary::telegram::DEFAULT_API_URL.to_string()),
+            long_message_strategy: twitter_news_summary::telegram::LongMessageStrategy::default(),
+            parse_mode: twitter_news_summary::telegram::ParseMode::default(),
+            delivery: twitter_news_summary::telegram::DeliveryLimits::default(),
             max_tweets: self.max_tweets,
             hours_lookback: self.hours_lookback,
             summary_max_tokens: self.summary_max_tokens,
             summary_max_words: self.summary_max_words,
+            summary_chunk_threshold_tokens: 30000,
+            summary_map_concurrency: 4,
+            summary_continuity_digests: 2,
+            embeddings: None,
+            prompts: twitter_news_summary::prompts::PromptLibrary::default(),
             nitter_instance: self.nitter_instance.clone(),
             nitter_api_key: self.nitter_api_key.clone(),
             usernames_file: self.usernames_file.clone(),
@@ -199,6 +205,9 @@ impl ExperimentConfig {
             database_url: "unused".to_string(),
             schedule_times: vec![],
             port: 8080,
+            circuit_breaker: Default::default(),
+            topics: vec![twitter_news_summary::topics::DigestTopic::default()],
+            topic: twitter_news_summary::topics::DigestTopic::default(),
         }
     }
 }
@@ -419,11 +428,10 @@ async fn summarize_command(config: &ExperimentConfig) -> Result<()> {
         return Ok(());
     }
 
-    // Adjust token limit for reasoning models
+    // Adjust token limit to the model (reasoning models need 16000+)
     let mut adjusted_config = config.clone();
-    if is_reasoning_model(&config.openai_model) {
-        adjusted_config.summary_max_tokens = 16000;
-    }
+    adjusted_config.summary_max_tokens =
+        model_spec(&config.openai_model).completion_tokens(config.summary_max_tokens);
 
     let full_config = adjusted_config.to_full_config();
 
@@ -84,13 +84,31 @@ impl PreviewConfig {
             openai_model: self.openai_model.clone(),
             openai_api_url: self.openai_api_url.clone(),
             openai_temperature: 0.7,
+            models: twitter_news_summary::models::ModelRegistry::builtin(),
+            openai_prices: twitter_news_summary::usage::PriceTable::default(),
+            openai_monthly_budget_usd: None,
+            openai_fallback_models: Vec::new(),
+            openai_fallback_api_key: None,
             telegram_bot_token: "unused".to_string(),
             telegram_chat_id: "unused".to_string(),
             telegram_webhook_secret: "unused".to_string(),
+            telegram_api_url: std::env::var("TELEGRAM_API_URL")
+                .unwrap_or_else(|_| twitter_news_summary::telegram::DEFAULT_API_URL.to_string()),
+            long_message_strategy: twitter_news_summary::telegram::LongMessageStrategy::default(),
+            parse_mode: std::env::var("TELEGRAM_PARSE_MODE")
+                .ok()
+                .and_then(|v| twitter_news_summary::telegram::ParseMode::parse(&v))
+                .unwrap_or_default(),
+            delivery: twitter_news_summary::telegram::DeliveryLimits::default(),
             max_tweets: self.max_tweets,
             hours_lookback: self.hours_lookback,
             summary_max_tokens: self.summary_max_tokens,
             summary_max_words: self.summary_max_words,
+            summary_chunk_threshold_tokens: 30000,
+            summary_map_concurrency: 4,
+            summary_continuity_digests: 2,
+            embeddings: None,
+            prompts: twitter_news_summary::prompts::PromptLibrary::default(),
             nitter_instance: self.nitter_instance.clone(),
             nitter_api_key: self.nitter_api_key.clone(),
             usernames_file: self.usernames_file.clone(),
@@ -98,6 +116,9 @@ impl PreviewConfig {
             database_url: "unused".to_string(),
             schedule_times: vec![],
             port: 8080,
+            circuit_breaker: Default::default(),
+            topics: vec![twitter_news_summary::topics::DigestTopic::default()],
+            topic: twitter_news_summary::topics::DigestTopic::default(),
         }
     }
 }
@@ -217,13 +238,14 @@ async fn main() -> Result<()> {
     let client = reqwest::Client::new();
     let summary = openai::summarize_tweets(&client, &config, &tweets).await?;
 
-    // Format the message exactly as Telegram would receive it (MarkdownV2)
+    // Format the message exactly as Telegram would receive it
+    let mode = config.parse_mode;
     let timestamp = Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
-    let escaped_timestamp = telegram::escape_markdownv2(&timestamp);
     let formatted_message = format!(
-        "📰 *Twitter Summary*\n_{}_\n\n{}",
-        escaped_timestamp,
-        telegram::escape_markdownv2(&summary)
+        "📰 {}\n{}\n\n{}",
+        mode.bold("Twitter Summary"),
+        mode.italic(&mode.escape(&timestamp)),
+        mode.render(&summary)
     );
 
     // Save to run-history/
@@ -239,7 +261,7 @@ async fn main() -> Result<()> {
          **Time window:** last {} hours\n\
          **Source:** {}\n\n\
          ---\n\n\
-         ## Telegram Message (MarkdownV2)\n\n\
+         ## Telegram Message ({})\n\n\
          ```\n{}\n```\n\n\
          ---\n\n\
          ## Raw Summary (from OpenAI)\n\n\
@@ -255,6 +277,7 @@ async fn main() -> Result<()> {
         } else {
             "fresh fetch"
         },
+        mode.as_str(),
         formatted_message,
         summary,
         tweets
@@ -296,7 +319,7 @@ async fn main() -> Result<()> {
     );
     println!("╚══════════════════════════════════════════════════════════════════╝");
     println!();
-    println!("--- MarkdownV2 Message (as sent to Telegram) ---");
+    println!("--- {} Message (as sent to Telegram) ---", mode.as_str());
     println!();
     println!("{}", formatted_message);
     println!();
@@ -339,9 +362,12 @@ async fn main() -> Result<()> {
             ..config
         };
 
+        let telegram_client = telegram::TelegramClient::from_config(&test_config);
+
         // Send message
         match twitter_news_summary::telegram::send_test_message(
-            &test_config,
+            &telegram_client,
+            mode,
             &test_chat_id,
             &summary,
         )
@@ -0,0 +1,228 @@
+//! Cross-digest continuity: what the previous digests already covered.
+//!
+//! Consecutive digests are summarized from overlapping tweet windows, so the
+//! same headline tends to come back. [`PreviousCoverage`] collects the headlines
+//! and URLs of recent summaries; they are passed to the summarizer as "already
+//! covered" context, and URLs sent within [`REPEAT_WINDOW_HOURS`] are flagged by
+//! the linter (see `DigestLinter::with_recent_urls`).
+
+use super::{Digest, DigestLinter};
+use crate::db::Summary;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+
+/// Linking a URL sent within this many hours is flagged as a repeat
+pub const REPEAT_WINDOW_HOURS: i64 = 24;
+
+/// A story sent in an earlier digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoveredStory {
+    pub headline: String,
+    pub url: Option<String>,
+}
+
+/// The stories of one earlier digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoveredDigest {
+    pub sent_at: DateTime<Utc>,
+    pub stories: Vec<CoveredStory>,
+}
+
+/// Headlines and URLs of recent digests, newest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreviousCoverage {
+    digests: Vec<CoveredDigest>,
+}
+
+impl PreviousCoverage {
+    /// Collect coverage from saved summaries (structured digest when stored,
+    /// otherwise the bullets of the Markdown text)
+    pub fn from_summaries(summaries: &[Summary]) -> Self {
+        let mut coverage = Self::default();
+        for summary in summaries {
+            match summary.digest() {
+                Some(digest) => coverage.add_digest(&digest, summary.created_at),
+                None => coverage.add_markdown(&summary.content, summary.created_at),
+            }
+        }
+        coverage
+    }
+
+    /// Add a structured digest sent at `sent_at`
+    pub fn add_digest(&mut self, digest: &Digest, sent_at: DateTime<Utc>) {
+        let stories = digest
+            .bullets()
+            .map(|b| CoveredStory {
+                headline: b.title.clone(),
+                url: Some(b.url.clone()),
+            })
+            .collect();
+        self.push(CoveredDigest { sent_at, stories });
+    }
+
+    /// Add a free-form Markdown summary sent at `sent_at`
+    pub fn add_markdown(&mut self, summary: &str, sent_at: DateTime<Utc>) {
+        let stories = DigestLinter::new()
+            .bullet_stories(summary)
+            .into_iter()
+            .map(|(headline, url)| CoveredStory { headline, url })
+            .collect();
+        self.push(CoveredDigest { sent_at, stories });
+    }
+
+    fn push(&mut self, digest: CoveredDigest) {
+        if digest.stories.is_empty() {
+            return;
+        }
+        self.digests.push(digest);
+        self.digests.sort_by_key(|d| std::cmp::Reverse(d.sent_at));
+    }
+
+    /// Earlier digests, newest first
+    pub fn digests(&self) -> &[CoveredDigest] {
+        &self.digests
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    /// "- headline (url)" lines for the newest `max_digests` digests, without
+    /// repeating a URL or headline; None if there is nothing to list
+    pub fn prompt_list(&self, max_digests: usize) -> Option<String> {
+        let mut seen = HashSet::new();
+        let lines: Vec<String> = self
+            .digests
+            .iter()
+            .take(max_digests)
+            .flat_map(|d| d.stories.iter())
+            .filter(|story| seen.insert(story.url.clone().unwrap_or(story.headline.clone())))
+            .map(|story| match &story.url {
+                Some(url) => format!("- {} ({})", story.headline, url),
+                None => format!("- {}", story.headline),
+            })
+            .collect();
+
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// URLs sent within `REPEAT_WINDOW_HOURS` before `now`
+    pub fn recent_urls(&self, now: DateTime<Utc>) -> HashSet<String> {
+        let cutoff = now - Duration::hours(REPEAT_WINDOW_HOURS);
+        self.digests
+            .iter()
+            .filter(|d| d.sent_at >= cutoff)
+            .flat_map(|d| d.stories.iter().filter_map(|s| s.url.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::{DigestBullet, DigestSection};
+
+    fn digest(bullets: &[(&str, &str)]) -> Digest {
+        Digest {
+            sections: vec![DigestSection {
+                title: "🧠 Top takeaways".to_string(),
+                bullets: bullets
+                    .iter()
+                    .map(|(title, url)| DigestBullet {
+                        title: title.to_string(),
+                        significance: "why it matters".to_string(),
+                        link_label: "Acme launch announcement post".to_string(),
+                        url: url.to_string(),
+                        source_tweet_ids: vec![],
+                        story: None,
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    fn summary(id: i64, hours_ago: i64, digest: Option<&Digest>, content: &str) -> Summary {
+        Summary {
+            id,
+            digest_id: "default".to_string(),
+            content: content.to_string(),
+            created_at: Utc::now() - Duration::hours(hours_ago),
+            digest_json: digest.map(|d| d.to_json().unwrap()),
+            lint_report_json: None,
+            prompt_version: None,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_from_summaries_reads_digest_and_markdown() {
+        let structured = digest(&[("Acme shipped Widget 2", "https://x.com/acme/status/1")]);
+        let coverage = PreviousCoverage::from_summaries(&[
+            summary(2, 12, Some(&structured), "ignored"),
+            summary(
+                1,
+                24,
+                None,
+                "🧠 Top takeaways\n- *Foo raised $10M* — growth [Foo funding post](https://x.com/foo/status/2)",
+            ),
+        ]);
+
+        let digests = coverage.digests();
+        assert_eq!(digests.len(), 2);
+        assert_eq!(digests[0].stories[0].headline, "Acme shipped Widget 2");
+        assert_eq!(digests[1].stories[0].headline, "Foo raised $10M");
+        assert_eq!(
+            digests[1].stories[0].url.as_deref(),
+            Some("https://x.com/foo/status/2")
+        );
+    }
+
+    #[test]
+    fn test_prompt_list_limits_digests_and_dedupes() {
+        let mut coverage = PreviousCoverage::default();
+        let now = Utc::now();
+        coverage.add_digest(
+            &digest(&[("Old story", "https://x.com/a/status/1")]),
+            now - Duration::hours(36),
+        );
+        coverage.add_digest(
+            &digest(&[
+                ("Acme shipped Widget 2", "https://x.com/acme/status/2"),
+                ("Acme shipped Widget 2 again", "https://x.com/acme/status/2"),
+            ]),
+            now - Duration::hours(12),
+        );
+
+        assert_eq!(
+            coverage.prompt_list(1).unwrap(),
+            "- Acme shipped Widget 2 (https://x.com/acme/status/2)"
+        );
+        assert!(coverage.prompt_list(2).unwrap().contains("Old story"));
+        assert_eq!(PreviousCoverage::default().prompt_list(2), None);
+    }
+
+    #[test]
+    fn test_recent_urls_uses_24_hour_window() {
+        let now = Utc::now();
+        let mut coverage = PreviousCoverage::default();
+        coverage.add_digest(
+            &digest(&[("Recent", "https://x.com/a/status/1")]),
+            now - Duration::hours(12),
+        );
+        coverage.add_digest(
+            &digest(&[("Stale", "https://x.com/a/status/2")]),
+            now - Duration::hours(25),
+        );
+
+        assert_eq!(
+            coverage.recent_urls(now),
+            HashSet::from(["https://x.com/a/status/1".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_summary_without_bullets_is_skipped() {
+        let coverage = PreviousCoverage::from_summaries(&[summary(1, 1, None, "No news today.")]);
+        assert!(coverage.is_empty());
+    }
+}
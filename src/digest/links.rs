@@ -0,0 +1,567 @@
+//! Verification of summary links against the input tweets.
+//!
+//! The model may only link the `Link:` URLs from `format_tweets_for_prompt` (and
+//! URLs that appear inside the tweet text), but it sometimes invents or mangles
+//! status IDs. [`LinkVerifier`] checks every link in a summary; unknown tweet
+//! links are repaired by matching the status ID or author against the input,
+//! and bullets whose link cannot be repaired are removed.
+
+use super::Digest;
+use crate::twitter::Tweet;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+static STATUS_URL_REGEX: OnceLock<Regex> = OnceLock::new();
+static TEXT_URL_REGEX: OnceLock<Regex> = OnceLock::new();
+static MARKDOWN_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Maximum edit distance between a mangled status ID and an input status ID
+/// from the same author for the link to be repaired
+const MAX_STATUS_ID_DISTANCE: usize = 2;
+
+/// Shorter status IDs are never fuzzy-matched (a couple of edits could turn
+/// them into any other ID); real snowflake IDs are 18-19 digits
+const MIN_FUZZY_STATUS_ID_LEN: usize = 10;
+
+/// A link that was rewritten to a known input URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkRepair {
+    pub from: String,
+    pub to: String,
+}
+
+/// Result of verifying the links of one summary
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LinkReport {
+    /// Links that matched an input URL as-is
+    pub verified: usize,
+    /// Links rewritten to an input URL
+    pub repaired: Vec<LinkRepair>,
+    /// Unverifiable links (their bullet was removed)
+    pub removed: Vec<String>,
+}
+
+impl LinkReport {
+    /// Check if every link matched an input URL as-is
+    pub fn is_clean(&self) -> bool {
+        self.repaired.is_empty() && self.removed.is_empty()
+    }
+
+    fn record(&mut self, original: &str, resolved: String) {
+        if resolved == original {
+            self.verified += 1;
+        } else {
+            self.repaired.push(LinkRepair {
+                from: original.to_string(),
+                to: resolved,
+            });
+        }
+    }
+}
+
+/// Known input URLs for one summarization run.
+#[derive(Debug, Clone, Default)]
+pub struct LinkVerifier {
+    /// Normalized form of every known URL
+    known: HashSet<String>,
+    /// Status ID -> canonical tweet URL
+    by_status: HashMap<String, String>,
+    /// Lowercase author -> (status ID, canonical tweet URL)
+    by_author: HashMap<String, Vec<(String, String)>>,
+}
+
+impl LinkVerifier {
+    /// Build the set of allowed URLs: each tweet's own URL plus any URL in its text.
+    pub fn from_tweets(tweets: &[Tweet]) -> Self {
+        let mut verifier = Self::default();
+        let text_url_regex =
+            TEXT_URL_REGEX.get_or_init(|| Regex::new(r"https?://[^\s)\]]+").unwrap());
+
+        for tweet in tweets {
+            if let Some(author) = &tweet.author_id {
+                if tweet.id != "unknown" {
+                    verifier.add_url(&format!("https://x.com/{}/status/{}", author, tweet.id));
+                }
+            }
+            for m in text_url_regex.find_iter(&tweet.text) {
+                verifier.add_url(m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?']));
+            }
+        }
+
+        verifier
+    }
+
+    fn add_url(&mut self, url: &str) {
+        self.known.insert(normalize_url(url));
+        if let Some((author, id)) = parse_status_url(url) {
+            let canonical = format!("https://x.com/{}/status/{}", author, id);
+            if self
+                .by_status
+                .insert(id.clone(), canonical.clone())
+                .is_none()
+            {
+                self.by_author
+                    .entry(author.to_lowercase())
+                    .or_default()
+                    .push((id, canonical));
+            }
+        }
+    }
+
+    /// Resolve a link to a known input URL.
+    ///
+    /// Returns the URL if it is known (tweet links in canonical form, so a wrong
+    /// author or host is fixed by the status ID), a repaired URL if the bullet's
+    /// source tweet IDs or a near-identical status ID from the same author match
+    /// an input tweet, and None if it cannot be verified.
+    pub fn resolve(&self, url: &str, source_tweet_ids: &[String]) -> Option<String> {
+        if self.known.contains(&normalize_url(url)) {
+            // Known, but rewrite tweet links to the canonical form
+            return Some(match parse_status_url(url) {
+                Some((_, id)) => self
+                    .by_status
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| url.to_string()),
+                None => url.to_string(),
+            });
+        }
+
+        if let Some(found) = source_tweet_ids
+            .iter()
+            .find_map(|id| self.by_status.get(id))
+        {
+            return Some(found.clone());
+        }
+
+        let (author, id) = parse_status_url(url)?;
+        if id.len() < MIN_FUZZY_STATUS_ID_LEN {
+            return None;
+        }
+        let candidates = self.by_author.get(&author.to_lowercase())?;
+        candidates
+            .iter()
+            .map(|(candidate, url)| (edit_distance(candidate, &id), url))
+            .filter(|(distance, _)| *distance <= MAX_STATUS_ID_DISTANCE)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, url)| url.clone())
+    }
+
+    /// Verify every bullet link in a digest, repairing or removing bullets in place.
+    ///
+    /// Sections left without bullets are removed.
+    pub fn verify_digest(&self, digest: &mut Digest) -> LinkReport {
+        let mut report = LinkReport::default();
+
+        for section in &mut digest.sections {
+            section.bullets.retain_mut(|bullet| {
+                match self.resolve(&bullet.url, &bullet.source_tweet_ids) {
+                    Some(url) => {
+                        report.record(&bullet.url, url.clone());
+                        bullet.url = url;
+                        true
+                    }
+                    None => {
+                        report.removed.push(bullet.url.clone());
+                        false
+                    }
+                }
+            });
+        }
+        digest.sections.retain(|s| !s.bullets.is_empty());
+
+        report
+    }
+
+    /// Verify every markdown link in a free-form summary.
+    ///
+    /// Bullets with an unverifiable link are dropped; on other lines the link is
+    /// replaced by its label. A section whose bullets were all dropped is removed.
+    pub fn verify_markdown(&self, summary: &str) -> (String, LinkReport) {
+        let link_regex = MARKDOWN_LINK_REGEX
+            .get_or_init(|| Regex::new(r"\[([^\]]+)\]\((https?://[^)\s]+)\)").unwrap());
+        let mut report = LinkReport::default();
+
+        let blocks: Vec<String> = summary
+            .split("\n\n")
+            .filter_map(|block| {
+                let mut removed_bullet = false;
+                let lines: Vec<String> = block
+                    .lines()
+                    .filter_map(|line| {
+                        let is_bullet = line.trim_start().starts_with("- ");
+                        let mut unverified = false;
+                        let rewritten = link_regex.replace_all(line, |caps: &regex::Captures| {
+                            match self.resolve(&caps[2], &[]) {
+                                Some(url) => {
+                                    report.record(&caps[2], url.clone());
+                                    format!("[{}]({})", &caps[1], url)
+                                }
+                                None => {
+                                    report.removed.push(caps[2].to_string());
+                                    unverified = true;
+                                    caps[1].to_string()
+                                }
+                            }
+                        });
+                        if unverified && is_bullet {
+                            removed_bullet = true;
+                            None
+                        } else {
+                            Some(rewritten.into_owned())
+                        }
+                    })
+                    .collect();
+
+                let has_bullets = lines.iter().any(|l| l.trim_start().starts_with("- "));
+                if removed_bullet && !has_bullets {
+                    None
+                } else {
+                    Some(lines.join("\n"))
+                }
+            })
+            .collect();
+
+        (blocks.join("\n\n"), report)
+    }
+}
+
+/// Extract (author, status ID) from an x.com / twitter.com status URL
+fn parse_status_url(url: &str) -> Option<(String, String)> {
+    let regex = STATUS_URL_REGEX.get_or_init(|| {
+        Regex::new(
+            r"^https?://(?:www\.|mobile\.)?(?:x|twitter)\.com/([A-Za-z0-9_]+)/status(?:es)?/(\d+)",
+        )
+        .unwrap()
+    });
+    let caps = regex.captures(url)?;
+    Some((caps[1].to_string(), caps[2].to_string()))
+}
+
+/// Normalize a URL for comparison: tweet links become "status/<id>" (author
+/// and host are ignored), other links drop the scheme, "www.", trailing slash
+/// and host case.
+fn normalize_url(url: &str) -> String {
+    if let Some((_, id)) = parse_status_url(url) {
+        return format!("status/{}", id);
+    }
+
+    let without_scheme = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    let without_www = without_scheme
+        .strip_prefix("www.")
+        .unwrap_or(without_scheme);
+    let (host, path) = without_www.split_once('/').unwrap_or((without_www, ""));
+    format!("{}/{}", host.to_lowercase(), path.trim_end_matches('/'))
+}
+
+/// Levenshtein distance between two short strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Global counters for link verification outcomes.
+pub struct LinkMetrics {
+    /// Links that matched an input URL
+    verified: AtomicUsize,
+    /// Links rewritten to an input URL
+    repaired: AtomicUsize,
+    /// Unverifiable links whose bullet was removed
+    removed: AtomicUsize,
+}
+
+static LINK_METRICS: OnceLock<LinkMetrics> = OnceLock::new();
+
+impl LinkMetrics {
+    /// Get the global link metrics instance.
+    pub fn global() -> &'static LinkMetrics {
+        LINK_METRICS.get_or_init(|| LinkMetrics {
+            verified: AtomicUsize::new(0),
+            repaired: AtomicUsize::new(0),
+            removed: AtomicUsize::new(0),
+        })
+    }
+
+    /// Add the outcomes of one verification run.
+    pub fn record(&self, report: &LinkReport) {
+        self.verified.fetch_add(report.verified, Ordering::Relaxed);
+        self.repaired
+            .fetch_add(report.repaired.len(), Ordering::Relaxed);
+        self.removed
+            .fetch_add(report.removed.len(), Ordering::Relaxed);
+    }
+
+    /// Generate a metrics report.
+    pub fn report(&self) -> LinkMetricsReport {
+        LinkMetricsReport {
+            verified: self.verified.load(Ordering::Relaxed),
+            repaired: self.repaired.load(Ordering::Relaxed),
+            removed: self.removed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reset all metrics to zero (useful for testing).
+    #[cfg(test)]
+    pub fn reset(&self) {
+        self.verified.store(0, Ordering::Relaxed);
+        self.repaired.store(0, Ordering::Relaxed);
+        self.removed.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of the link verification counters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkMetricsReport {
+    pub verified: usize,
+    pub repaired: usize,
+    pub removed: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::{DigestBullet, DigestSection};
+    use serial_test::serial;
+
+    fn tweet(author: &str, id: &str, text: &str) -> Tweet {
+        Tweet {
+            id: id.to_string(),
+            text: text.to_string(),
+            author_id: Some(author.to_string()),
+            created_at: None,
+            topic: None,
+        }
+    }
+
+    fn verifier() -> LinkVerifier {
+        LinkVerifier::from_tweets(&[
+            tweet("OpenAI", "1866000000000000001", "GPT-5 is here"),
+            tweet(
+                "karpathy",
+                "1866000000000000002",
+                "New video: https://youtube.com/watch?v=abc.",
+            ),
+            tweet("karpathy", "1866000000000000099", "Another post"),
+        ])
+    }
+
+    fn bullet(url: &str, source_tweet_ids: &[&str]) -> DigestBullet {
+        DigestBullet {
+            title: "Title".to_string(),
+            significance: String::new(),
+            link_label: "Some descriptive label".to_string(),
+            url: url.to_string(),
+            source_tweet_ids: source_tweet_ids.iter().map(|s| s.to_string()).collect(),
+            story: None,
+        }
+    }
+
+    // ==================== resolve Tests ====================
+
+    #[test]
+    fn test_resolve_known_tweet_url() {
+        let url = "https://x.com/OpenAI/status/1866000000000000001";
+        assert_eq!(verifier().resolve(url, &[]), Some(url.to_string()));
+    }
+
+    #[test]
+    fn test_resolve_canonicalizes_twitter_host() {
+        assert_eq!(
+            verifier().resolve("https://twitter.com/openai/status/1866000000000000001", &[]),
+            Some("https://x.com/OpenAI/status/1866000000000000001".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_known_outbound_link() {
+        let v = verifier();
+        assert!(v.resolve("https://youtube.com/watch?v=abc", &[]).is_some());
+        assert!(v
+            .resolve("https://www.youtube.com/watch?v=abc/", &[])
+            .is_some());
+    }
+
+    #[test]
+    fn test_resolve_status_id_with_wrong_author() {
+        assert_eq!(
+            verifier().resolve("https://x.com/sama/status/1866000000000000001", &[]),
+            Some("https://x.com/OpenAI/status/1866000000000000001".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_mangled_status_id_same_author() {
+        assert_eq!(
+            verifier().resolve("https://x.com/karpathy/status/1866000000000000012", &[]),
+            Some("https://x.com/karpathy/status/1866000000000000002".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_uses_source_tweet_ids() {
+        assert_eq!(
+            verifier().resolve(
+                "https://x.com/OpenAI/status/42",
+                &["1866000000000000002".to_string()]
+            ),
+            Some("https://x.com/karpathy/status/1866000000000000002".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_does_not_fuzzy_match_short_ids() {
+        let v = LinkVerifier::from_tweets(&[tweet("acme", "1", "short id")]);
+        assert!(v.resolve("https://x.com/acme/status/2", &[]).is_none());
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_links() {
+        let v = verifier();
+        assert!(v
+            .resolve("https://x.com/karpathy/status/1111111111111111111", &[])
+            .is_none());
+        assert!(v
+            .resolve("https://x.com/nobody/status/1866000000000000003", &[])
+            .is_none());
+        assert!(v.resolve("https://example.com/made-up", &[]).is_none());
+    }
+
+    // ==================== verify_digest Tests ====================
+
+    #[test]
+    fn test_verify_digest_repairs_and_removes() {
+        let mut digest = Digest {
+            sections: vec![
+                DigestSection {
+                    title: "🧠 Top takeaways".to_string(),
+                    bullets: vec![
+                        bullet("https://x.com/OpenAI/status/1866000000000000001", &[]),
+                        bullet("https://x.com/sama/status/1866000000000000002", &[]),
+                    ],
+                },
+                DigestSection {
+                    title: "🔬 Research".to_string(),
+                    bullets: vec![bullet("https://example.com/made-up", &[])],
+                },
+            ],
+        };
+
+        let report = verifier().verify_digest(&mut digest);
+
+        assert_eq!(report.verified, 1);
+        assert_eq!(report.repaired.len(), 1);
+        assert_eq!(report.removed, vec!["https://example.com/made-up"]);
+        assert_eq!(digest.sections.len(), 1, "empty section should be dropped");
+        assert_eq!(
+            digest.urls(),
+            vec![
+                "https://x.com/OpenAI/status/1866000000000000001",
+                "https://x.com/karpathy/status/1866000000000000002"
+            ]
+        );
+    }
+
+    // ==================== verify_markdown Tests ====================
+
+    #[test]
+    fn test_verify_markdown_keeps_clean_summary() {
+        let summary = "🧠 Top takeaways\n- *GPT-5* — big [OpenAI GPT-5 post](https://x.com/OpenAI/status/1866000000000000001)";
+        let (verified, report) = verifier().verify_markdown(summary);
+        assert_eq!(verified, summary);
+        assert!(report.is_clean());
+        assert_eq!(report.verified, 1);
+    }
+
+    #[test]
+    fn test_verify_markdown_repairs_and_drops_bullets() {
+        let summary = "🧠 Top takeaways\n\
+- *A* [OpenAI post](https://x.com/OpenAI/status/1866000000000000001)\n\
+- *B* [Karpathy post](https://x.com/karpathy/status/1866000000000000012)\n\
+\n\
+🔬 Research\n\
+- *C* [Made up paper](https://x.com/nobody/status/5)";
+
+        let (verified, report) = verifier().verify_markdown(summary);
+
+        assert_eq!(
+            verified,
+            "🧠 Top takeaways\n\
+- *A* [OpenAI post](https://x.com/OpenAI/status/1866000000000000001)\n\
+- *B* [Karpathy post](https://x.com/karpathy/status/1866000000000000002)"
+        );
+        assert_eq!(report.verified, 1);
+        assert_eq!(report.repaired.len(), 1);
+        assert_eq!(report.removed, vec!["https://x.com/nobody/status/5"]);
+    }
+
+    #[test]
+    fn test_verify_markdown_unlinks_non_bullet_lines() {
+        let summary = "Intro with [a fake link](https://example.com/fake)\n- *A* [OpenAI post](https://x.com/OpenAI/status/1866000000000000001)";
+        let (verified, report) = verifier().verify_markdown(summary);
+        assert!(verified.starts_with("Intro with a fake link\n"));
+        assert_eq!(report.removed.len(), 1);
+    }
+
+    // ==================== Helper Tests ====================
+
+    #[test]
+    fn test_parse_status_url() {
+        assert_eq!(
+            parse_status_url("https://twitter.com/a_b/status/123?s=20"),
+            Some(("a_b".to_string(), "123".to_string()))
+        );
+        assert_eq!(parse_status_url("https://x.com/a_b"), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("123", "123"), 0);
+        assert_eq!(edit_distance("123", "124"), 1);
+        assert_eq!(edit_distance("123", "1234"), 1);
+        assert_eq!(edit_distance("", "12"), 2);
+    }
+
+    // ==================== Metrics Tests ====================
+
+    #[test]
+    #[serial]
+    fn test_link_metrics_record() {
+        let metrics = LinkMetrics::global();
+        metrics.reset();
+
+        metrics.record(&LinkReport {
+            verified: 3,
+            repaired: vec![LinkRepair {
+                from: "a".to_string(),
+                to: "b".to_string(),
+            }],
+            removed: vec!["c".to_string(), "d".to_string()],
+        });
+
+        assert_eq!(
+            metrics.report(),
+            LinkMetricsReport {
+                verified: 3,
+                repaired: 1,
+                removed: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,695 @@
+//! Post-generation linter for the summary rules in `build_system_prompt`.
+//!
+//! The linter works on the canonical Markdown text (the rendered digest or the
+//! free-form fallback), so both generation paths are checked the same way.
+//! Each violation has a human-readable description that is logged and sent
+//! back to the model in the corrective request.
+
+use crate::i18n::ENGLISH_HEADERS;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+static LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Link labels the prompt forbids (compared case-insensitively)
+const GENERIC_LINK_LABELS: [&str; 13] = [
+    "read more",
+    "learn more",
+    "here",
+    "link",
+    "thread",
+    "watch",
+    "details",
+    "source",
+    "sources",
+    "source post",
+    "source tweet",
+    "source link",
+    "original source",
+];
+
+/// A single rule violation found in a summary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum LintViolation {
+    /// The summary does not start with the Top takeaways section
+    MissingTopTakeaways,
+    /// A section heading that is not in the allowed list
+    UnknownSection { section: String },
+    /// A section with too few or too many bullets
+    BulletCount {
+        section: String,
+        count: usize,
+        min: usize,
+        max: usize,
+    },
+    /// A bullet without exactly one markdown link
+    LinkCount { bullet: String, count: usize },
+    /// The same URL is linked more than once
+    DuplicateUrl { url: String },
+    /// A generic link label ("Read more", "source", ...)
+    GenericLinkLabel { label: String },
+    /// A link label outside the allowed word count
+    LinkLabelLength {
+        label: String,
+        words: usize,
+        min: usize,
+        max: usize,
+    },
+    /// A Top takeaways item repeated in a later section
+    RepeatedTopTakeaway { bullet: String, section: String },
+    /// A link to a URL already sent in a recent digest (see `continuity`)
+    RecentlySentUrl { bullet: String, url: String },
+}
+
+impl fmt::Display for LintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTopTakeaways => {
+                write!(
+                    f,
+                    "The digest must start with the \"🧠 Top takeaways\" section"
+                )
+            }
+            Self::UnknownSection { section } => {
+                write!(
+                    f,
+                    "Section \"{}\" is not one of the allowed sections",
+                    section
+                )
+            }
+            Self::BulletCount {
+                section,
+                count,
+                min,
+                max,
+            } => write!(
+                f,
+                "Section \"{}\" has {} bullets (must have {}-{})",
+                section, count, min, max
+            ),
+            Self::LinkCount { bullet, count } => write!(
+                f,
+                "Bullet \"{}\" has {} links (must end with exactly one)",
+                bullet, count
+            ),
+            Self::DuplicateUrl { url } => {
+                write!(f, "URL {} is used more than once", url)
+            }
+            Self::GenericLinkLabel { label } => write!(
+                f,
+                "Link label \"{}\" is generic (name the person, org or artifact)",
+                label
+            ),
+            Self::LinkLabelLength {
+                label,
+                words,
+                min,
+                max,
+            } => write!(
+                f,
+                "Link label \"{}\" has {} words (must have {}-{})",
+                label, words, min, max
+            ),
+            Self::RepeatedTopTakeaway { bullet, section } => write!(
+                f,
+                "Bullet \"{}\" in \"{}\" repeats a Top takeaways item",
+                bullet, section
+            ),
+            Self::RecentlySentUrl { bullet, url } => write!(
+                f,
+                "Bullet \"{}\" links {}, which was already sent in the last 24 hours \
+                 (drop it, or title it \"Update: ...\" and link the tweet with the new information)",
+                bullet, url
+            ),
+        }
+    }
+}
+
+/// Lint results recorded with a summary
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintReport {
+    /// Violations found in the first draft
+    pub violations: Vec<LintViolation>,
+    /// Whether a corrective request was made
+    pub repair_attempted: bool,
+    /// Violations left in the summary that was kept
+    pub remaining: Vec<LintViolation>,
+}
+
+impl LintReport {
+    /// Report for a draft that was not (yet) repaired
+    pub fn new(violations: Vec<LintViolation>) -> Self {
+        Self {
+            remaining: violations.clone(),
+            violations,
+            repair_attempted: false,
+        }
+    }
+
+    /// Check if the kept summary has no violations
+    pub fn is_clean(&self) -> bool {
+        self.remaining.is_empty()
+    }
+
+    /// Serialize the report to JSON (for storage).
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).context("Failed to serialize lint report")
+    }
+}
+
+/// Checks a summary against the hard requirements of the system prompt.
+#[derive(Debug, Clone)]
+pub struct DigestLinter {
+    top_section: &'static str,
+    allowed_sections: Vec<String>,
+    top_bullets: RangeInclusive<usize>,
+    section_bullets: RangeInclusive<usize>,
+    label_words: RangeInclusive<usize>,
+    /// URLs sent in recent digests; linking one again is a violation
+    recent_urls: HashSet<String>,
+}
+
+impl Default for DigestLinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A section as parsed from the summary text
+struct ParsedSection {
+    title: String,
+    bullets: Vec<ParsedBullet>,
+}
+
+/// A bullet as parsed from the summary text
+struct ParsedBullet {
+    text: String,
+    /// The bold "What happened" part, if present
+    headline: Option<String>,
+    /// (label, url) pairs
+    links: Vec<(String, String)>,
+}
+
+impl DigestLinter {
+    /// Create a linter with the limits from `build_system_prompt`
+    pub fn new() -> Self {
+        Self::with_sections(&ENGLISH_HEADERS[1..])
+    }
+
+    /// Create a linter for a digest whose topic sections differ from the defaults
+    pub fn with_sections<S: AsRef<str>>(sections: &[S]) -> Self {
+        Self {
+            top_section: ENGLISH_HEADERS[0],
+            allowed_sections: sections.iter().map(|s| s.as_ref().to_string()).collect(),
+            top_bullets: 3..=5,
+            section_bullets: 2..=4,
+            label_words: 3..=8,
+            recent_urls: HashSet::new(),
+        }
+    }
+
+    /// Also flag bullets that link any of `urls` (sent in recent digests)
+    pub fn with_recent_urls(mut self, urls: HashSet<String>) -> Self {
+        self.recent_urls = urls;
+        self
+    }
+
+    /// Lint a Markdown summary and return every violation found (in text order).
+    pub fn lint(&self, summary: &str) -> Vec<LintViolation> {
+        let sections = self.parse(summary);
+        let mut violations = Vec::new();
+
+        if sections.first().map(|s| s.title.as_str()) != Some(self.top_section) {
+            violations.push(LintViolation::MissingTopTakeaways);
+        }
+
+        // URL -> index of the section that first used it
+        let mut seen_urls: HashMap<&str, usize> = HashMap::new();
+        let top_headlines: Vec<String> = sections
+            .iter()
+            .filter(|s| s.title == self.top_section)
+            .flat_map(|s| s.bullets.iter().filter_map(|b| b.headline.as_deref()))
+            .map(normalize_headline)
+            .collect();
+
+        for (s_idx, section) in sections.iter().enumerate() {
+            let is_top = section.title == self.top_section;
+            if !is_top && !self.allowed_sections.contains(&section.title) {
+                violations.push(LintViolation::UnknownSection {
+                    section: section.title.clone(),
+                });
+            }
+
+            let range = if is_top {
+                &self.top_bullets
+            } else {
+                &self.section_bullets
+            };
+            if !range.contains(&section.bullets.len()) {
+                violations.push(LintViolation::BulletCount {
+                    section: section.title.clone(),
+                    count: section.bullets.len(),
+                    min: *range.start(),
+                    max: *range.end(),
+                });
+            }
+
+            for bullet in &section.bullets {
+                let name = bullet
+                    .headline
+                    .clone()
+                    .unwrap_or_else(|| bullet.text.clone());
+
+                if bullet.links.len() != 1 {
+                    violations.push(LintViolation::LinkCount {
+                        bullet: name.clone(),
+                        count: bullet.links.len(),
+                    });
+                }
+
+                let repeats_top = !is_top
+                    && bullet
+                        .headline
+                        .as_deref()
+                        .is_some_and(|h| top_headlines.contains(&normalize_headline(h)));
+                let mut reported_repeat = false;
+                if repeats_top {
+                    violations.push(LintViolation::RepeatedTopTakeaway {
+                        bullet: name.clone(),
+                        section: section.title.clone(),
+                    });
+                    reported_repeat = true;
+                }
+
+                for (label, url) in &bullet.links {
+                    self.lint_label(label, &mut violations);
+
+                    if self.recent_urls.contains(url) {
+                        violations.push(LintViolation::RecentlySentUrl {
+                            bullet: name.clone(),
+                            url: url.clone(),
+                        });
+                    }
+
+                    match seen_urls.get(url.as_str()) {
+                        Some(&first) if sections[first].title == self.top_section && !is_top => {
+                            if !reported_repeat {
+                                violations.push(LintViolation::RepeatedTopTakeaway {
+                                    bullet: name.clone(),
+                                    section: section.title.clone(),
+                                });
+                                reported_repeat = true;
+                            }
+                        }
+                        Some(_) => {
+                            violations.push(LintViolation::DuplicateUrl { url: url.clone() })
+                        }
+                        None => {
+                            seen_urls.insert(url, s_idx);
+                        }
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+
+    fn lint_label(&self, label: &str, violations: &mut Vec<LintViolation>) {
+        let lower = label.trim().to_lowercase();
+        if GENERIC_LINK_LABELS.contains(&lower.as_str()) {
+            violations.push(LintViolation::GenericLinkLabel {
+                label: label.to_string(),
+            });
+            return;
+        }
+
+        let words = label.split_whitespace().count();
+        if !self.label_words.contains(&words) {
+            violations.push(LintViolation::LinkLabelLength {
+                label: label.to_string(),
+                words,
+                min: *self.label_words.start(),
+                max: *self.label_words.end(),
+            });
+        }
+    }
+
+    /// Each bullet's bold headline (or full text) and first link URL, in text order
+    pub(super) fn bullet_stories(&self, summary: &str) -> Vec<(String, Option<String>)> {
+        self.parse(summary)
+            .into_iter()
+            .flat_map(|s| s.bullets)
+            .map(|b| {
+                let url = b.links.into_iter().next().map(|(_, url)| url);
+                (b.headline.unwrap_or(b.text), url)
+            })
+            .collect()
+    }
+
+    /// Split the summary into sections and bullets.
+    ///
+    /// A non-bullet line is a heading when it is a known section header, the
+    /// first line, or follows a blank line; otherwise it continues the previous
+    /// bullet (wrapped text).
+    fn parse(&self, summary: &str) -> Vec<ParsedSection> {
+        let mut sections: Vec<ParsedSection> = Vec::new();
+        let mut after_blank = true;
+
+        for raw in summary.lines() {
+            let line = raw.trim();
+            if line.is_empty() {
+                after_blank = true;
+                continue;
+            }
+
+            if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("• ")) {
+                if sections.is_empty() {
+                    sections.push(ParsedSection {
+                        title: String::new(),
+                        bullets: Vec::new(),
+                    });
+                }
+                let section = sections.last_mut().expect("section exists");
+                section.bullets.push(ParsedBullet {
+                    text: text.to_string(),
+                    headline: None,
+                    links: Vec::new(),
+                });
+            } else {
+                let heading = line.trim_matches('*').trim();
+                let is_heading = after_blank
+                    || sections.is_empty()
+                    || heading == self.top_section
+                    || self.allowed_sections.iter().any(|s| s == heading);
+
+                match sections.last_mut().and_then(|s| s.bullets.last_mut()) {
+                    Some(bullet) if !is_heading => {
+                        bullet.text.push(' ');
+                        bullet.text.push_str(line);
+                    }
+                    _ => sections.push(ParsedSection {
+                        title: heading.to_string(),
+                        bullets: Vec::new(),
+                    }),
+                }
+            }
+            after_blank = false;
+        }
+
+        let link_regex =
+            LINK_REGEX.get_or_init(|| Regex::new(r"\[([^\]]+)\]\((https?://[^)\s]+)\)").unwrap());
+        for bullet in sections.iter_mut().flat_map(|s| s.bullets.iter_mut()) {
+            bullet.headline = bullet
+                .text
+                .strip_prefix('*')
+                .and_then(|rest| rest.split_once('*'))
+                .map(|(headline, _)| headline.trim().to_string());
+            bullet.links = link_regex
+                .captures_iter(&bullet.text)
+                .map(|c| (c[1].to_string(), c[2].to_string()))
+                .collect();
+        }
+
+        sections
+    }
+}
+
+/// Lowercase and keep only alphanumerics so near-identical headlines compare equal
+fn normalize_headline(headline: &str) -> String {
+    headline
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bullet(headline: &str, label: &str, url_id: u32) -> String {
+        format!(
+            "- *{}* — concrete impact [{}](https://x.com/acme/status/{})",
+            headline, label, url_id
+        )
+    }
+
+    /// A summary that satisfies every rule
+    fn clean_summary() -> String {
+        [
+            "🧠 Top takeaways".to_string(),
+            bullet("Acme released Widget 2", "Acme Widget 2 launch post", 1),
+            bullet("Foo Labs published a paper", "Foo Labs scaling paper", 2),
+            bullet("Bar raised $50M", "Bar Series B announcement", 3),
+            String::new(),
+            "🔬 Research".to_string(),
+            bullet(
+                "Baz shared benchmark results",
+                "Baz MMLU benchmark thread",
+                4,
+            ),
+            bullet("Qux open-sourced a dataset", "Qux dataset release notes", 5),
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn test_clean_summary_has_no_violations() {
+        let violations = DigestLinter::new().lint(&clean_summary());
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn test_missing_top_takeaways() {
+        let summary = clean_summary().replace("🧠 Top takeaways", "🚀 Releases");
+        let violations = DigestLinter::new().lint(&summary);
+        assert!(violations.contains(&LintViolation::MissingTopTakeaways));
+    }
+
+    #[test]
+    fn test_unknown_section() {
+        let summary = clean_summary().replace("🔬 Research", "📰 Misc News");
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(
+            violations,
+            vec![LintViolation::UnknownSection {
+                section: "📰 Misc News".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bullet_count_limits() {
+        let summary = format!(
+            "{}\n\n🚀 Releases\n{}",
+            clean_summary(),
+            bullet("Lone release", "Lone Corp release notes", 6)
+        );
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(
+            violations,
+            vec![LintViolation::BulletCount {
+                section: "🚀 Releases".to_string(),
+                count: 1,
+                min: 2,
+                max: 4
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bullet_without_link() {
+        let summary = clean_summary().replace(
+            " [Qux dataset release notes](https://x.com/acme/status/5)",
+            "",
+        );
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(
+            violations,
+            vec![LintViolation::LinkCount {
+                bullet: "Qux open-sourced a dataset".to_string(),
+                count: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_url_in_topic_sections() {
+        let summary = format!(
+            "{}\n\n🚀 Releases\n{}\n{}",
+            clean_summary(),
+            bullet("Release one", "Acme Widget 3 changelog", 7),
+            bullet("Release two", "Acme Widget 4 changelog", 5)
+        );
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(
+            violations,
+            vec![LintViolation::DuplicateUrl {
+                url: "https://x.com/acme/status/5".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_generic_link_labels() {
+        let summary = clean_summary()
+            .replace("Acme Widget 2 launch post", "Read more")
+            .replace("Foo Labs scaling paper", "Source tweet");
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(
+            violations,
+            vec![
+                LintViolation::GenericLinkLabel {
+                    label: "Read more".to_string()
+                },
+                LintViolation::GenericLinkLabel {
+                    label: "Source tweet".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_descriptive_label_containing_source_passes() {
+        let summary = clean_summary()
+            .replace(
+                "Acme Widget 2 launch post",
+                "Acme open-source release notes",
+            )
+            .replace(
+                "Foo Labs scaling paper",
+                "Sourcegraph engineering blog post",
+            );
+        assert!(DigestLinter::new().lint(&summary).is_empty());
+    }
+
+    #[test]
+    fn test_link_label_word_count() {
+        let summary = clean_summary().replace("Bar Series B announcement", "Bar");
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(
+            violations,
+            vec![LintViolation::LinkLabelLength {
+                label: "Bar".to_string(),
+                words: 1,
+                min: 3,
+                max: 8
+            }]
+        );
+    }
+
+    #[test]
+    fn test_top_takeaway_repeated_by_url() {
+        let summary = clean_summary().replace("status/5", "status/1");
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(
+            violations,
+            vec![LintViolation::RepeatedTopTakeaway {
+                bullet: "Qux open-sourced a dataset".to_string(),
+                section: "🔬 Research".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_top_takeaway_repeated_by_headline() {
+        let summary =
+            clean_summary().replace("Qux open-sourced a dataset", "Acme released Widget 2!");
+        let violations = DigestLinter::new().lint(&summary);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0],
+            LintViolation::RepeatedTopTakeaway { .. }
+        ));
+    }
+
+    #[test]
+    fn test_wrapped_bullet_is_joined() {
+        let summary = clean_summary().replace(
+            " [Qux dataset release notes]",
+            "\n  with more detail [Qux dataset release notes]",
+        );
+        assert!(DigestLinter::new().lint(&summary).is_empty());
+    }
+
+    #[test]
+    fn test_custom_sections_replace_defaults() {
+        let summary = clean_summary().replace("🔬 Research", "🦀 Crates");
+        assert!(DigestLinter::with_sections(&["🦀 Crates"])
+            .lint(&summary)
+            .is_empty());
+
+        let violations = DigestLinter::with_sections(&["🦀 Crates"]).lint(&clean_summary());
+        assert!(matches!(
+            &violations[..],
+            [LintViolation::UnknownSection { section }] if section == "🔬 Research"
+        ));
+    }
+
+    #[test]
+    fn test_recently_sent_url_is_flagged() {
+        let recent = HashSet::from(["https://x.com/acme/status/4".to_string()]);
+        let violations = DigestLinter::new()
+            .with_recent_urls(recent)
+            .lint(&clean_summary());
+        assert_eq!(
+            violations,
+            vec![LintViolation::RecentlySentUrl {
+                bullet: "Baz shared benchmark results".to_string(),
+                url: "https://x.com/acme/status/4".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bullet_stories() {
+        let stories = DigestLinter::new().bullet_stories(&clean_summary());
+        assert_eq!(stories.len(), 5);
+        assert_eq!(
+            stories[0],
+            (
+                "Acme released Widget 2".to_string(),
+                Some("https://x.com/acme/status/1".to_string())
+            )
+        );
+
+        let stories = DigestLinter::new().bullet_stories("- plain bullet without link");
+        assert_eq!(
+            stories,
+            vec![("plain bullet without link".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn test_violation_display() {
+        let violation = LintViolation::BulletCount {
+            section: "🚀 Releases".to_string(),
+            count: 1,
+            min: 2,
+            max: 4,
+        };
+        assert_eq!(
+            violation.to_string(),
+            "Section \"🚀 Releases\" has 1 bullets (must have 2-4)"
+        );
+    }
+
+    #[test]
+    fn test_lint_report_roundtrip() {
+        let report = LintReport::new(vec![LintViolation::DuplicateUrl {
+            url: "https://x.com/a/status/1".to_string(),
+        }]);
+        assert!(!report.is_clean());
+        let json = report.to_json().unwrap();
+        assert!(json.contains("\"rule\":\"duplicate_url\""));
+        let restored: LintReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, report);
+    }
+}
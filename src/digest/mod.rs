@@ -0,0 +1,378 @@
+//! Structured digest model.
+//!
+//! The summarizer asks the model for a JSON document matching [`Digest::json_schema`]
+//! instead of free-form Markdown. The parsed [`Digest`] is validated, stored alongside
+//! the rendered summary, and turned into Telegram MarkdownV2, HTML or plain text by
+//! the `render` submodule. Working on typed fields means downstream steps (translation,
+//! validation) no longer need to re-parse Markdown with regexes. The `lint` submodule
+//! checks generated summaries against the hard requirements of the system prompt,
+//! the `links` submodule rejects links that do not come from the input tweets, and
+//! the `continuity` submodule tracks what earlier digests already covered.
+//! Bullets that continue a developing story carry a [`StoryMarker`] (see `stories`).
+
+mod continuity;
+mod links;
+mod lint;
+mod render;
+
+pub use continuity::{CoveredDigest, CoveredStory, PreviousCoverage, REPEAT_WINDOW_HOURS};
+pub use links::{LinkMetrics, LinkMetricsReport, LinkRepair, LinkReport, LinkVerifier};
+pub use lint::{DigestLinter, LintReport, LintViolation};
+pub use render::RenderFormat;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A complete digest: an ordered list of sections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Digest {
+    pub sections: Vec<DigestSection>,
+}
+
+/// A digest section (e.g. "🧠 Top takeaways") with its bullets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestSection {
+    /// Section heading, emoji + words (e.g. "🚀 Releases")
+    pub title: String,
+    pub bullets: Vec<DigestBullet>,
+}
+
+/// A single digest bullet: what happened, why it matters, and one link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestBullet {
+    /// Short "what happened" headline (rendered in bold)
+    pub title: String,
+    /// Natural-language significance or consequence
+    pub significance: String,
+    /// Descriptive link label (3-8 words)
+    pub link_label: String,
+    /// Link target (must be one of the input tweet URLs)
+    pub url: String,
+    /// Status IDs of the tweets this bullet is based on
+    pub source_tweet_ids: Vec<String>,
+    /// Developing story this bullet continues (set after generation by
+    /// `stories::assign`, never by the model)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub story: Option<StoryMarker>,
+}
+
+/// Link from a bullet to a story that earlier digests already covered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoryMarker {
+    /// Story id (shown as the `/story_<id>` command)
+    pub id: i64,
+    /// Day of the story the digest is sent on, starting at 1
+    pub day: u32,
+}
+
+impl Digest {
+    /// Parse a digest from the model's JSON output and validate it.
+    ///
+    /// Tolerates a surrounding ```json code fence, which some models add even
+    /// when a response format is requested.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let trimmed = strip_code_fence(json.trim());
+        let digest: Digest =
+            serde_json::from_str(trimmed).context("Failed to parse digest JSON")?;
+        digest.validate()?;
+        Ok(digest)
+    }
+
+    /// Serialize the digest to JSON (for storage).
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).context("Failed to serialize digest")
+    }
+
+    /// Check structural invariants: at least one section, no empty sections,
+    /// and every bullet has a title, a link label and an http(s) URL.
+    pub fn validate(&self) -> Result<()> {
+        if self.sections.is_empty() {
+            bail!("Digest has no sections");
+        }
+
+        for (s_idx, section) in self.sections.iter().enumerate() {
+            if section.title.trim().is_empty() {
+                bail!("Section {} has an empty title", s_idx + 1);
+            }
+            if section.bullets.is_empty() {
+                bail!("Section '{}' has no bullets", section.title);
+            }
+
+            for (b_idx, bullet) in section.bullets.iter().enumerate() {
+                let location = format!("Bullet {} in '{}'", b_idx + 1, section.title);
+                if bullet.title.trim().is_empty() {
+                    bail!("{} has an empty title", location);
+                }
+                if bullet.link_label.trim().is_empty() {
+                    bail!("{} has an empty link label", location);
+                }
+                if !(bullet.url.starts_with("https://") || bullet.url.starts_with("http://")) {
+                    bail!("{} has an invalid URL: '{}'", location, bullet.url);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// All bullet URLs in digest order.
+    pub fn urls(&self) -> Vec<&str> {
+        self.bullets().map(|b| b.url.as_str()).collect()
+    }
+
+    /// Iterate over every bullet in digest order.
+    pub fn bullets(&self) -> impl Iterator<Item = &DigestBullet> {
+        self.sections.iter().flat_map(|s| s.bullets.iter())
+    }
+
+    /// The same digest without story markers (e.g. for sending to a model).
+    pub fn without_story_markers(&self) -> Digest {
+        let mut digest = self.clone();
+        for section in &mut digest.sections {
+            for bullet in &mut section.bullets {
+                bullet.story = None;
+            }
+        }
+        digest
+    }
+
+    /// Copy the story markers of `other`, bullet by bullet (the layouts must match).
+    pub fn copy_story_markers(&mut self, other: &Digest) {
+        let markers = other.bullets().map(|b| b.story);
+        for (bullet, marker) in self
+            .sections
+            .iter_mut()
+            .flat_map(|s| s.bullets.iter_mut())
+            .zip(markers)
+        {
+            bullet.story = marker;
+        }
+    }
+
+    /// Render the digest in the given output format.
+    pub fn render(&self, format: RenderFormat) -> String {
+        render::render(self, format)
+    }
+
+    /// JSON schema for the OpenAI `response_format` (strict mode).
+    ///
+    /// Strict mode requires every property to be listed in `required` and
+    /// `additionalProperties: false` on every object.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "sections": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "title": { "type": "string" },
+                            "bullets": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "title": { "type": "string" },
+                                        "significance": { "type": "string" },
+                                        "link_label": { "type": "string" },
+                                        "url": { "type": "string" },
+                                        "source_tweet_ids": {
+                                            "type": "array",
+                                            "items": { "type": "string" }
+                                        }
+                                    },
+                                    "required": ["title", "significance", "link_label", "url", "source_tweet_ids"],
+                                    "additionalProperties": false
+                                }
+                            }
+                        },
+                        "required": ["title", "bullets"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["sections"],
+            "additionalProperties": false
+        })
+    }
+
+    /// Complete `response_format` value for a chat completion request.
+    pub fn response_format() -> serde_json::Value {
+        serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "digest",
+                "strict": true,
+                "schema": Self::json_schema()
+            }
+        })
+    }
+}
+
+/// Remove a leading ```json / ``` fence and trailing ``` if present.
+fn strip_code_fence(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    let rest = rest.strip_prefix("json").unwrap_or(rest);
+    rest.strip_suffix("```").unwrap_or(rest).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_json() -> &'static str {
+        r#"{
+            "sections": [
+                {
+                    "title": "🧠 Top takeaways",
+                    "bullets": [
+                        {
+                            "title": "OpenAI released GPT-5",
+                            "significance": "a new flagship model with 400k context",
+                            "link_label": "OpenAI GPT-5 announcement",
+                            "url": "https://x.com/OpenAI/status/1",
+                            "source_tweet_ids": ["1"]
+                        }
+                    ]
+                },
+                {
+                    "title": "🔬 Research",
+                    "bullets": [
+                        {
+                            "title": "DeepMind published a RoPE paper",
+                            "significance": "shows 2x longer context at equal cost",
+                            "link_label": "DeepMind RoPE scaling paper",
+                            "url": "https://x.com/GoogleDeepMind/status/2",
+                            "source_tweet_ids": ["2", "3"]
+                        }
+                    ]
+                }
+            ]
+        }"#
+    }
+
+    #[test]
+    fn test_from_json_parses_valid_digest() {
+        let digest = Digest::from_json(sample_json()).expect("should parse");
+        assert_eq!(digest.sections.len(), 2);
+        assert_eq!(digest.sections[0].title, "🧠 Top takeaways");
+        assert_eq!(
+            digest.sections[1].bullets[0].source_tweet_ids,
+            vec!["2", "3"]
+        );
+    }
+
+    #[test]
+    fn test_from_json_strips_code_fence() {
+        let fenced = format!("```json\n{}\n```", sample_json());
+        let digest = Digest::from_json(&fenced).expect("should parse fenced JSON");
+        assert_eq!(digest.sections.len(), 2);
+    }
+
+    #[test]
+    fn test_from_json_rejects_malformed_json() {
+        let result = Digest::from_json("Here is your summary: ...");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Failed to parse digest JSON"));
+    }
+
+    #[test]
+    fn test_from_json_rejects_missing_fields() {
+        let json = r#"{"sections": [{"title": "🚀 Releases", "bullets": [{"title": "x"}]}]}"#;
+        assert!(Digest::from_json(json).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_digest() {
+        let digest = Digest { sections: vec![] };
+        let err = digest.validate().unwrap_err().to_string();
+        assert!(err.contains("no sections"));
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_section() {
+        let digest = Digest {
+            sections: vec![DigestSection {
+                title: "🚀 Releases".to_string(),
+                bullets: vec![],
+            }],
+        };
+        let err = digest.validate().unwrap_err().to_string();
+        assert!(err.contains("no bullets"));
+    }
+
+    #[test]
+    fn test_validate_rejects_non_http_url() {
+        let mut digest = Digest::from_json(sample_json()).unwrap();
+        digest.sections[0].bullets[0].url = "Link unavailable".to_string();
+        let err = digest.validate().unwrap_err().to_string();
+        assert!(err.contains("invalid URL"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_link_label() {
+        let mut digest = Digest::from_json(sample_json()).unwrap();
+        digest.sections[1].bullets[0].link_label = "  ".to_string();
+        let err = digest.validate().unwrap_err().to_string();
+        assert!(err.contains("empty link label"));
+    }
+
+    #[test]
+    fn test_urls_in_order() {
+        let digest = Digest::from_json(sample_json()).unwrap();
+        assert_eq!(
+            digest.urls(),
+            vec![
+                "https://x.com/OpenAI/status/1",
+                "https://x.com/GoogleDeepMind/status/2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let digest = Digest::from_json(sample_json()).unwrap();
+        let json = digest.to_json().unwrap();
+        let restored = Digest::from_json(&json).unwrap();
+        assert_eq!(digest, restored);
+    }
+
+    #[test]
+    fn test_story_markers_roundtrip_and_copy() {
+        let plain = Digest::from_json(sample_json()).unwrap();
+        assert!(!plain.to_json().unwrap().contains("story"));
+
+        let mut marked = plain.clone();
+        marked.sections[1].bullets[0].story = Some(StoryMarker { id: 4, day: 2 });
+        let restored = Digest::from_json(&marked.to_json().unwrap()).unwrap();
+        assert_eq!(restored, marked);
+
+        assert_eq!(marked.without_story_markers(), plain);
+        let mut copy = plain.clone();
+        copy.copy_story_markers(&marked);
+        assert_eq!(copy, marked);
+    }
+
+    #[test]
+    fn test_json_schema_is_strict() {
+        let schema = Digest::json_schema();
+        assert_eq!(schema["additionalProperties"], false);
+        let bullet = &schema["properties"]["sections"]["items"]["properties"]["bullets"]["items"];
+        assert_eq!(bullet["additionalProperties"], false);
+        assert_eq!(bullet["required"].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_response_format_wraps_schema() {
+        let format = Digest::response_format();
+        assert_eq!(format["type"], "json_schema");
+        assert_eq!(format["json_schema"]["strict"], true);
+        assert_eq!(format["json_schema"]["schema"], Digest::json_schema());
+    }
+}
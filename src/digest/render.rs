@@ -0,0 +1,254 @@
+//! Renderers that turn a structured [`Digest`] into output text.
+//!
+//! Every format uses the same layout: one heading line per section followed by
+//! its bullets, with a blank line between sections. Each bullet reads
+//! "- <title> — <significance> <link>", followed by "🧵 Day <n> · /story_<id>"
+//! when the bullet continues a developing story.
+
+use super::{Digest, DigestBullet, StoryMarker};
+use crate::telegram::{
+    escape_html, escape_markdownv2_link_text, escape_markdownv2_simple, escape_markdownv2_url,
+};
+
+/// Output format for [`Digest::render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    /// Unescaped Markdown in the same shape the model used to produce
+    /// (`*bold*` titles, `[label](url)` links). This is the canonical text
+    /// stored in `summaries.content` and fed to translation/condensing.
+    Markdown,
+    /// Telegram MarkdownV2 with all literal text escaped
+    MarkdownV2,
+    /// Telegram HTML parse mode
+    Html,
+    /// Plain text without any markup (links shown as "label: url")
+    PlainText,
+}
+
+pub(super) fn render(digest: &Digest, format: RenderFormat) -> String {
+    digest
+        .sections
+        .iter()
+        .map(|section| {
+            let mut lines = Vec::with_capacity(section.bullets.len() + 1);
+            lines.push(render_heading(&section.title, format));
+            lines.extend(section.bullets.iter().map(|b| match b.story {
+                Some(marker) => format!(
+                    "{} {}",
+                    render_bullet(b, format),
+                    render_story_marker(marker, format)
+                ),
+                None => render_bullet(b, format),
+            }));
+            lines.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn render_heading(title: &str, format: RenderFormat) -> String {
+    match format {
+        RenderFormat::Markdown | RenderFormat::PlainText => title.to_string(),
+        RenderFormat::MarkdownV2 => format!("*{}*", escape_markdownv2_simple(title)),
+        RenderFormat::Html => format!("<b>{}</b>", escape_html(title)),
+    }
+}
+
+fn render_bullet(bullet: &DigestBullet, format: RenderFormat) -> String {
+    let significance = bullet.significance.trim();
+
+    match format {
+        RenderFormat::Markdown => {
+            let body = join_parts(&format!("*{}*", bullet.title), significance, " — ");
+            format!("- {} [{}]({})", body, bullet.link_label, bullet.url)
+        }
+        RenderFormat::MarkdownV2 => {
+            let title = format!("*{}*", escape_markdownv2_simple(&bullet.title));
+            let body = join_parts(&title, &escape_markdownv2_simple(significance), " — ");
+            format!(
+                "\\- {} [{}]({})",
+                body,
+                escape_markdownv2_link_text(&bullet.link_label),
+                escape_markdownv2_url(&bullet.url)
+            )
+        }
+        RenderFormat::Html => {
+            let title = format!("<b>{}</b>", escape_html(&bullet.title));
+            let body = join_parts(&title, &escape_html(significance), " — ");
+            format!(
+                "- {} <a href=\"{}\">{}</a>",
+                body,
+                escape_html(&bullet.url),
+                escape_html(&bullet.link_label)
+            )
+        }
+        RenderFormat::PlainText => {
+            let body = join_parts(&bullet.title, significance, " — ");
+            format!("- {} ({}: {})", body, bullet.link_label, bullet.url)
+        }
+    }
+}
+
+/// "🧵 Day 3 · /story_12": the story's day and the command that shows its timeline
+fn render_story_marker(marker: StoryMarker, format: RenderFormat) -> String {
+    let text = format!("🧵 Day {} · /story_{}", marker.day, marker.id);
+    match format {
+        RenderFormat::Markdown | RenderFormat::Html | RenderFormat::PlainText => text,
+        RenderFormat::MarkdownV2 => escape_markdownv2_simple(&text),
+    }
+}
+
+/// Join a title and an optional tail with a separator (skips an empty tail).
+fn join_parts(title: &str, tail: &str, separator: &str) -> String {
+    if tail.is_empty() {
+        title.to_string()
+    } else {
+        format!("{}{}{}", title, separator, tail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::DigestSection;
+
+    fn bullet(title: &str, significance: &str, label: &str, url: &str) -> DigestBullet {
+        DigestBullet {
+            title: title.to_string(),
+            significance: significance.to_string(),
+            link_label: label.to_string(),
+            url: url.to_string(),
+            source_tweet_ids: vec![],
+            story: None,
+        }
+    }
+
+    fn sample_digest() -> Digest {
+        Digest {
+            sections: vec![
+                DigestSection {
+                    title: "🧠 Top takeaways".to_string(),
+                    bullets: vec![bullet(
+                        "OpenAI released GPT-5.1",
+                        "adds 1M context (beta)",
+                        "OpenAI GPT-5.1 post",
+                        "https://x.com/OpenAI/status/1",
+                    )],
+                },
+                DigestSection {
+                    title: "🧰 Tools and Tutorials".to_string(),
+                    bullets: vec![
+                        bullet(
+                            "LangChain shipped v1.0",
+                            "",
+                            "hwchase17 on LangChain v1",
+                            "https://x.com/hwchase17/status/2",
+                        ),
+                        bullet(
+                            "Karpathy <3 tokenizers",
+                            "explains BPE & merges",
+                            "Karpathy \"tokenizer\" video",
+                            "https://x.com/karpathy/status/3",
+                        ),
+                    ],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_matches_prompt_layout() {
+        let rendered = sample_digest().render(RenderFormat::Markdown);
+        let expected = "🧠 Top takeaways\n\
+- *OpenAI released GPT-5.1* — adds 1M context (beta) [OpenAI GPT-5.1 post](https://x.com/OpenAI/status/1)\n\
+\n\
+🧰 Tools and Tutorials\n\
+- *LangChain shipped v1.0* [hwchase17 on LangChain v1](https://x.com/hwchase17/status/2)\n\
+- *Karpathy <3 tokenizers* — explains BPE & merges [Karpathy \"tokenizer\" video](https://x.com/karpathy/status/3)";
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn test_render_markdownv2_escapes_literal_text_and_keeps_bold() {
+        let rendered = sample_digest().render(RenderFormat::MarkdownV2);
+        assert!(rendered.starts_with("*🧠 Top takeaways*\n"));
+        assert!(rendered.contains(
+            "\\- *OpenAI released GPT\\-5\\.1* — adds 1M context \\(beta\\) [OpenAI GPT\\-5\\.1 post](https://x.com/OpenAI/status/1)"
+        ));
+        // Empty significance: no dangling em-dash
+        assert!(rendered.contains("\\- *LangChain shipped v1\\.0* [hwchase17"));
+    }
+
+    #[test]
+    fn test_render_markdownv2_escapes_url_parenthesis() {
+        let digest = Digest {
+            sections: vec![DigestSection {
+                title: "🔬 Research".to_string(),
+                bullets: vec![bullet(
+                    "Paper",
+                    "",
+                    "Wiki page",
+                    "https://en.wikipedia.org/wiki/Foo_(bar)",
+                )],
+            }],
+        };
+        let rendered = digest.render(RenderFormat::MarkdownV2);
+        assert!(rendered.contains("(https://en.wikipedia.org/wiki/Foo_(bar\\))"));
+    }
+
+    #[test]
+    fn test_render_html_escapes_and_links() {
+        let rendered = sample_digest().render(RenderFormat::Html);
+        assert!(rendered.starts_with("<b>🧠 Top takeaways</b>\n"));
+        assert!(rendered.contains(
+            "- <b>Karpathy &lt;3 tokenizers</b> — explains BPE &amp; merges <a href=\"https://x.com/karpathy/status/3\">Karpathy &quot;tokenizer&quot; video</a>"
+        ));
+    }
+
+    #[test]
+    fn test_render_plain_text_has_no_markup() {
+        let rendered = sample_digest().render(RenderFormat::PlainText);
+        assert!(!rendered.contains('*'));
+        assert!(!rendered.contains("]("));
+        assert!(rendered.contains(
+            "- OpenAI released GPT-5.1 — adds 1M context (beta) (OpenAI GPT-5.1 post: https://x.com/OpenAI/status/1)"
+        ));
+    }
+
+    #[test]
+    fn test_render_separates_sections_with_blank_line() {
+        for format in [
+            RenderFormat::Markdown,
+            RenderFormat::MarkdownV2,
+            RenderFormat::Html,
+            RenderFormat::PlainText,
+        ] {
+            let rendered = sample_digest().render(format);
+            assert_eq!(rendered.matches("\n\n").count(), 1, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_render_story_marker_after_link() {
+        let mut digest = sample_digest();
+        digest.sections[0].bullets[0].story = Some(StoryMarker { id: 12, day: 3 });
+
+        let markdown = digest.render(RenderFormat::Markdown);
+        assert!(markdown.contains(
+            "[OpenAI GPT-5.1 post](https://x.com/OpenAI/status/1) 🧵 Day 3 · /story_12\n"
+        ));
+        let markdownv2 = digest.render(RenderFormat::MarkdownV2);
+        assert!(markdownv2.contains("(https://x.com/OpenAI/status/1) 🧵 Day 3 · /story\\_12\n"));
+        // Bullets without a marker are unchanged
+        assert!(markdown.ends_with("(https://x.com/karpathy/status/3)"));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("a < b & c > \"d\""),
+            "a &lt; b &amp; c &gt; &quot;d&quot;"
+        );
+        assert_eq!(escape_html("plain"), "plain");
+    }
+}
@@ -0,0 +1,346 @@
+//! Subscriber interests and personalized digests.
+//!
+//! Subscribers declare interests with `/interests`: digest sections (e.g.
+//! "Research"), keywords, and @accounts. When a structured digest is sent,
+//! the bullets matching a subscriber's interests come first, or are the only
+//! ones shown. Matching runs on the canonical (English) digest and gives a
+//! [`Selection`] of section and bullet positions, which is applied to any
+//! translation of the digest as well (translations keep the layout). No extra
+//! model call is made per subscriber, and subscribers whose interests select
+//! the same bullets share one rendering.
+
+use crate::digest::{Digest, DigestBullet, DigestSection};
+use serde::{Deserialize, Serialize};
+
+/// What a subscriber wants to read about (all names normalized to lowercase)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Interests {
+    /// Section names without their emoji, e.g. "research"
+    #[serde(default)]
+    pub sections: Vec<String>,
+    /// Words or phrases searched for in bullet titles, significance and link labels
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// X/Twitter handles without the "@"
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    /// Show only matching bullets (otherwise matching bullets come first)
+    #[serde(default)]
+    pub only: bool,
+}
+
+/// A section title without its leading emoji, lowercased ("🔬 Research" -> "research")
+pub fn section_name(title: &str) -> String {
+    title
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim()
+        .to_lowercase()
+}
+
+/// The author handle of an x.com/twitter.com status URL, lowercased
+fn url_handle(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let rest = rest.strip_prefix("www.").unwrap_or(rest);
+    let path = rest
+        .strip_prefix("x.com/")
+        .or_else(|| rest.strip_prefix("twitter.com/"))?;
+    let handle = path.split('/').next()?;
+    (!handle.is_empty()).then(|| handle.to_lowercase())
+}
+
+impl Interests {
+    /// Parse a comma-separated list: "@name" is an account, a name from
+    /// `known_sections` (with or without its emoji) is a section, and anything
+    /// else is a keyword. The mode is left at "matching bullets first".
+    pub fn parse(input: &str, known_sections: &[String]) -> Self {
+        let known: Vec<String> = known_sections.iter().map(|s| section_name(s)).collect();
+        let mut interests = Self::default();
+
+        for item in input.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            if let Some(handle) = item.strip_prefix('@') {
+                let handle = handle.trim().to_lowercase();
+                if !handle.is_empty() {
+                    interests.accounts.push(handle);
+                }
+                continue;
+            }
+            let name = section_name(item);
+            if known.contains(&name) {
+                interests.sections.push(name);
+            } else if !name.is_empty() {
+                interests.keywords.push(item.to_lowercase());
+            }
+        }
+
+        for list in [
+            &mut interests.sections,
+            &mut interests.keywords,
+            &mut interests.accounts,
+        ] {
+            list.sort();
+            list.dedup();
+        }
+        interests
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty() && self.keywords.is_empty() && self.accounts.is_empty()
+    }
+
+    /// Whether a bullet of the section titled `section_title` matches
+    pub fn matches(&self, section_title: &str, bullet: &DigestBullet) -> bool {
+        if self.sections.contains(&section_name(section_title)) {
+            return true;
+        }
+
+        let text = format!(
+            "{} {} {}",
+            bullet.title, bullet.significance, bullet.link_label
+        )
+        .to_lowercase();
+        if self.keywords.iter().any(|k| text.contains(k.as_str())) {
+            return true;
+        }
+
+        let author = url_handle(&bullet.url);
+        self.accounts.iter().any(|account| {
+            author.as_deref() == Some(account.as_str()) || text.contains(&format!("@{}", account))
+        })
+    }
+
+    /// The bullets of `digest` to show, in order. None when the subscriber
+    /// should get the digest as is: no interests, or nothing matches.
+    pub fn select(&self, digest: &Digest) -> Option<Selection> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // (section, matching bullets, other bullets)
+        let sections: Vec<(usize, Vec<usize>, Vec<usize>)> = digest
+            .sections
+            .iter()
+            .enumerate()
+            .map(|(s_idx, section)| {
+                let (matching, other) = (0..section.bullets.len())
+                    .partition(|&b_idx| self.matches(&section.title, &section.bullets[b_idx]));
+                (s_idx, matching, other)
+            })
+            .collect();
+
+        if sections.iter().all(|(_, matching, _)| matching.is_empty()) {
+            return None;
+        }
+
+        let selected = if self.only {
+            sections
+                .into_iter()
+                .filter(|(_, matching, _)| !matching.is_empty())
+                .map(|(s_idx, matching, _)| (s_idx, matching))
+                .collect()
+        } else {
+            // Sections with matches first; matching bullets first in each section
+            let (with, without): (Vec<_>, Vec<_>) = sections
+                .into_iter()
+                .partition(|(_, matching, _)| !matching.is_empty());
+            with.into_iter()
+                .chain(without)
+                .map(|(s_idx, mut matching, other)| {
+                    matching.extend(other);
+                    (s_idx, matching)
+                })
+                .collect()
+        };
+        Some(Selection(selected))
+    }
+}
+
+/// Sections (by position) and, for each, the positions of its bullets to show
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Selection(Vec<(usize, Vec<usize>)>);
+
+impl Selection {
+    /// Apply the selection to `digest` (the canonical digest or a translation
+    /// of it). Positions missing from `digest` are skipped.
+    pub fn apply(&self, digest: &Digest) -> Digest {
+        let sections = self
+            .0
+            .iter()
+            .filter_map(|(s_idx, bullets)| {
+                let section = digest.sections.get(*s_idx)?;
+                let bullets: Vec<DigestBullet> = bullets
+                    .iter()
+                    .filter_map(|b_idx| section.bullets.get(*b_idx).cloned())
+                    .collect();
+                (!bullets.is_empty()).then(|| DigestSection {
+                    title: section.title.clone(),
+                    bullets,
+                })
+            })
+            .collect();
+        Digest { sections }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bullet(title: &str, url: &str) -> DigestBullet {
+        DigestBullet {
+            title: title.to_string(),
+            significance: "why it matters".to_string(),
+            link_label: "source post".to_string(),
+            url: url.to_string(),
+            source_tweet_ids: vec![],
+            story: None,
+        }
+    }
+
+    fn sample_digest() -> Digest {
+        Digest {
+            sections: vec![
+                DigestSection {
+                    title: "🧠 Top takeaways".to_string(),
+                    bullets: vec![
+                        bullet("Acme ships Widget 2", "https://x.com/acme/status/1"),
+                        bullet("Rust 2.0 announced", "https://x.com/rustlang/status/2"),
+                    ],
+                },
+                DigestSection {
+                    title: "🚀 Releases".to_string(),
+                    bullets: vec![bullet("Foo 1.0 is out", "https://x.com/foo/status/3")],
+                },
+                DigestSection {
+                    title: "🔬 Research".to_string(),
+                    bullets: vec![
+                        bullet("Scaling paper", "https://x.com/lab/status/4"),
+                        bullet("Thread by @Karpathy", "https://x.com/someone/status/5"),
+                    ],
+                },
+            ],
+        }
+    }
+
+    fn titles(digest: &Digest) -> Vec<&str> {
+        digest.bullets().map(|b| b.title.as_str()).collect()
+    }
+
+    fn known_sections() -> Vec<String> {
+        vec!["🚀 Releases".to_string(), "🔬 Research".to_string()]
+    }
+
+    // ==================== Parsing Tests ====================
+
+    #[test]
+    fn test_parse_sorts_items_into_sections_keywords_and_accounts() {
+        let interests = Interests::parse(
+            "Research, 🚀 releases, Rust, @Karpathy, rust, , @",
+            &known_sections(),
+        );
+        assert_eq!(interests.sections, vec!["releases", "research"]);
+        assert_eq!(interests.keywords, vec!["rust"]);
+        assert_eq!(interests.accounts, vec!["karpathy"]);
+        assert!(!interests.only);
+    }
+
+    #[test]
+    fn test_parse_empty_input() {
+        assert!(Interests::parse(" , ", &known_sections()).is_empty());
+    }
+
+    #[test]
+    fn test_section_name_strips_emoji() {
+        assert_eq!(section_name("🔬 Research"), "research");
+        assert_eq!(section_name("🧰 Crates and Tools"), "crates and tools");
+        assert_eq!(section_name("Research"), "research");
+    }
+
+    #[test]
+    fn test_url_handle() {
+        assert_eq!(
+            url_handle("https://x.com/OpenAI/status/1").as_deref(),
+            Some("openai")
+        );
+        assert_eq!(
+            url_handle("https://www.twitter.com/foo/status/1").as_deref(),
+            Some("foo")
+        );
+        assert_eq!(url_handle("https://example.com/foo"), None);
+    }
+
+    // ==================== Selection Tests ====================
+
+    #[test]
+    fn test_select_puts_matches_first() {
+        let interests = Interests::parse("research, rust", &known_sections());
+        let digest = sample_digest();
+
+        let personalized = interests.select(&digest).unwrap().apply(&digest);
+
+        assert_eq!(
+            titles(&personalized),
+            vec![
+                "Rust 2.0 announced",
+                "Acme ships Widget 2",
+                "Scaling paper",
+                "Thread by @Karpathy",
+                "Foo 1.0 is out",
+            ]
+        );
+        assert_eq!(personalized.sections[2].title, "🚀 Releases");
+    }
+
+    #[test]
+    fn test_select_only_matching() {
+        let mut interests = Interests::parse("@karpathy, @acme", &known_sections());
+        interests.only = true;
+        let digest = sample_digest();
+
+        let personalized = interests.select(&digest).unwrap().apply(&digest);
+
+        assert_eq!(
+            titles(&personalized),
+            vec!["Acme ships Widget 2", "Thread by @Karpathy"]
+        );
+        assert_eq!(personalized.sections.len(), 2);
+        assert!(personalized.validate().is_ok());
+    }
+
+    #[test]
+    fn test_select_none_without_matches() {
+        let digest = sample_digest();
+        assert_eq!(Interests::default().select(&digest), None);
+
+        let mut interests = Interests::parse("quantum", &known_sections());
+        interests.only = true;
+        assert_eq!(interests.select(&digest), None);
+    }
+
+    #[test]
+    fn test_selection_applies_to_translation() {
+        let interests = Interests::parse("releases", &known_sections());
+        let digest = sample_digest();
+        let mut translated = digest.clone();
+        translated.sections[1].title = "🚀 Lanzamientos".to_string();
+        translated.sections[1].bullets[0].title = "Foo 1.0 ya está disponible".to_string();
+
+        let selection = interests.select(&digest).unwrap();
+        let personalized = selection.apply(&translated);
+
+        assert_eq!(personalized.sections[0].title, "🚀 Lanzamientos");
+        assert_eq!(
+            personalized.sections[0].bullets[0].title,
+            "Foo 1.0 ya está disponible"
+        );
+    }
+
+    #[test]
+    fn test_equal_selections_share_cache_key() {
+        let digest = sample_digest();
+        let by_section = Interests::parse("releases", &known_sections());
+        let by_keyword = Interests::parse("foo", &known_sections());
+        assert_eq!(by_section.select(&digest), by_keyword.select(&digest));
+    }
+}
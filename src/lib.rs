@@ -1,11 +1,20 @@
 pub mod config;
 pub mod db;
+pub mod digest;
+pub mod embeddings;
 pub mod i18n;
+pub mod interests;
+pub mod map_reduce;
+pub mod models;
 pub mod openai;
+pub mod prompts;
 pub mod retry;
 pub mod rss;
 pub mod scheduler;
 pub mod security;
+pub mod stories;
 pub mod telegram;
+pub mod topics;
 pub mod translation;
 pub mod twitter;
+pub mod usage;
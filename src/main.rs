@@ -8,17 +8,49 @@ use axum::{
 };
 use std::sync::Arc;
 use tracing::{info, warn};
-use twitter_news_summary::{config, db, i18n::TranslationMetrics, scheduler, security, telegram};
+use twitter_news_summary::{
+    config, db, digest::LinkMetrics, i18n::TranslationMetrics, retry::CircuitBreakers, scheduler,
+    security, telegram, topics, usage::UsageTracker,
+};
 
 struct AppState {
     config: Arc<config::Config>,
     db: Arc<db::Database>,
+    telegram: Arc<telegram::TelegramClient>,
 }
 
 #[derive(serde::Deserialize)]
 struct TestParams {
     chat_id: Option<String>,
     fresh: Option<bool>,
+    /// Digest id (defaults to the main digest)
+    digest: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct TriggerParams {
+    /// Digest id (defaults to every digest)
+    digest: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct UsageParams {
+    /// "day" (default) or "month"
+    period: Option<String>,
+    /// Number of periods to include (default 30 days or 12 months)
+    limit: Option<i32>,
+}
+
+#[derive(serde::Deserialize)]
+struct RatingsParams {
+    /// Number of days of ratings to include (default 30)
+    days: Option<i32>,
+}
+
+#[derive(serde::Deserialize)]
+struct ResumeParams {
+    /// Summary whose delivery to resume
+    summary_id: i64,
 }
 
 #[derive(serde::Deserialize)]
@@ -67,7 +99,7 @@ async fn main() -> Result<()> {
     // Warn if API_KEY is not configured
     if config.api_key.is_none() {
         warn!(
-            "⚠️  API_KEY not configured - /trigger, /subscribers, /broadcast, and /translation-metrics endpoints will be unprotected"
+            "⚠️  API_KEY not configured - /trigger, /subscribers, /broadcast, /translation-metrics, /link-metrics, /circuit-breakers, /deliveries, /deliveries/resume, /usage, and /ratings endpoints will be unprotected"
         );
     }
 
@@ -75,14 +107,41 @@ async fn main() -> Result<()> {
     let db = Arc::new(db::Database::new(&config.database_url).await?);
     info!("✓ Database initialized");
 
+    // Persist OpenAI usage and restore the month-to-date cost for the budget cap
+    if let Err(e) = UsageTracker::global()
+        .attach_database(db.as_ref().clone())
+        .await
+    {
+        warn!("Failed to enable usage tracking: {:#}", e);
+    }
+
+    // Shared Telegram Bot API client
+    let telegram = Arc::new(telegram::TelegramClient::from_config(&config));
+    match telegram.get_me().await {
+        Ok(bot) => info!(
+            "✓ Telegram client ready (@{}, {})",
+            bot.username.as_deref().unwrap_or("unknown"),
+            telegram.base_url()
+        ),
+        Err(e) => warn!("Telegram getMe failed: {:#}", e),
+    }
+
     // Start scheduler
-    let _scheduler = scheduler::start_scheduler(Arc::clone(&config), Arc::clone(&db)).await?;
-    info!("✓ Scheduler started for times: {:?}", config.schedule_times);
+    let _scheduler =
+        scheduler::start_scheduler(Arc::clone(&config), Arc::clone(&db), Arc::clone(&telegram))
+            .await?;
+    for topic in &config.topics {
+        info!(
+            "✓ Scheduler started for digest '{}' at times: {:?}",
+            topic.id, topic.schedule_times
+        );
+    }
 
     // Create app state
     let state = Arc::new(AppState {
         config: Arc::clone(&config),
         db: Arc::clone(&db),
+        telegram,
     });
 
     // Build router
@@ -94,6 +153,12 @@ async fn main() -> Result<()> {
         .route("/subscribers", get(subscribers_handler))
         .route("/broadcast", post(broadcast_handler))
         .route("/translation-metrics", get(translation_metrics_handler))
+        .route("/link-metrics", get(link_metrics_handler))
+        .route("/circuit-breakers", get(circuit_breakers_handler))
+        .route("/deliveries", get(deliveries_handler))
+        .route("/deliveries/resume", post(resume_delivery_handler))
+        .route("/usage", get(usage_handler))
+        .route("/ratings", get(ratings_handler))
         .with_state(state);
 
     // Start server
@@ -135,7 +200,7 @@ async fn webhook_handler(
 
     info!("Received webhook: update_id={}", update.update_id);
 
-    match telegram::handle_webhook(&state.config, &state.db, update).await {
+    match telegram::handle_webhook(&state.config, &state.db, &state.telegram, update).await {
         Ok(_) => StatusCode::OK,
         Err(e) => {
             // Log the error but return OK to prevent Telegram from retrying.
@@ -151,6 +216,7 @@ async fn webhook_handler(
 async fn trigger_handler(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
+    Query(params): Query<TriggerParams>,
 ) -> impl IntoResponse {
     // Check API key with constant-time comparison
     if let Some(expected_key) = &state.config.api_key {
@@ -169,16 +235,35 @@ async fn trigger_handler(
         }
     }
 
-    info!("Manual trigger requested");
+    let result = match params.digest.as_deref() {
+        Some(digest_id) => {
+            let Some(topic) = topics::find_topic(&state.config.topics, digest_id) else {
+                return (StatusCode::BAD_REQUEST, "Unknown digest").into_response();
+            };
+            info!("Manual trigger requested for digest '{}'", digest_id);
+            scheduler::trigger_summary(&state.config.for_topic(topic), &state.db, &state.telegram)
+                .await
+        }
+        None => {
+            info!("Manual trigger requested");
+            scheduler::trigger_all_summaries(&state.config, &state.db, &state.telegram).await
+        }
+    };
 
-    match scheduler::trigger_summary(&state.config, &state.db).await {
+    match result {
         Ok(_) => {
             info!("Manual trigger completed successfully");
             (StatusCode::OK, "Summary sent").into_response()
         }
         Err(e) => {
             warn!("Manual trigger failed: {:?}", e);
-            telegram::notify_admin_error(&state.config, "Manual trigger (/trigger)", &e).await;
+            telegram::notify_admin_error(
+                &state.config,
+                &state.telegram,
+                "Manual trigger (/trigger)",
+                &e,
+            )
+            .await;
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Error: {}", e)).into_response()
         }
     }
@@ -222,13 +307,24 @@ async fn test_handler(
             .into_response();
     }
 
-    info!("Test message requested for chat_id: {}", chat_id);
+    let config = match params.digest.as_deref() {
+        Some(digest_id) => match topics::find_topic(&state.config.topics, digest_id) {
+            Some(topic) => state.config.for_topic(topic),
+            None => return (StatusCode::BAD_REQUEST, "Unknown digest").into_response(),
+        },
+        None => state.config.as_ref().clone(),
+    };
+
+    info!(
+        "Test message requested for chat_id: {} (digest: {})",
+        chat_id, config.topic.id
+    );
 
     // Get or generate summary
     let summary = if params.fresh.unwrap_or(false) {
         info!("Generating fresh summary for test (no broadcast)");
         // Generate fresh summary WITHOUT broadcasting to all subscribers
-        match scheduler::generate_summary_only(&state.config, &state.db).await {
+        match scheduler::generate_summary_only(&config, &state.db, &state.telegram).await {
             Ok(summary) => summary,
             Err(e) => {
                 warn!("Failed to generate summary: {}", e);
@@ -238,7 +334,7 @@ async fn test_handler(
         }
     } else {
         // Use latest from DB
-        match state.db.get_latest_summary().await {
+        match state.db.get_latest_digest_summary(&config.topic.id).await {
             Ok(Some(summary)) => summary.content,
             Ok(None) => {
                 warn!("No summary available in database");
@@ -253,7 +349,9 @@ async fn test_handler(
     };
 
     // Send test message
-    match telegram::send_test_message(&state.config, &chat_id, &summary).await {
+    match telegram::send_test_message(&state.telegram, state.config.parse_mode, &chat_id, &summary)
+        .await
+    {
         Ok(_) => {
             info!("Test message sent successfully to {}", chat_id);
             (StatusCode::OK, format!("Test message sent to {}", chat_id)).into_response()
@@ -375,6 +473,7 @@ async fn broadcast_handler(
     match telegram::broadcast_message(
         &state.config,
         &state.db,
+        &state.telegram,
         &request.message,
         request.parse_mode.as_deref(),
     )
@@ -449,3 +548,367 @@ async fn translation_metrics_handler(
     let report = TranslationMetrics::global().report();
     (StatusCode::OK, Json(report)).into_response()
 }
+
+/// Link metrics endpoint (API key protected) - returns summary link verification counts
+async fn link_metrics_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    // Check API key with constant-time comparison
+    if let Some(expected_key) = &state.config.api_key {
+        match headers.get("X-API-Key") {
+            Some(header_value) => {
+                let provided_key = header_value.to_str().unwrap_or("");
+                if !security::constant_time_compare(provided_key, expected_key) {
+                    warn!("Unauthorized link metrics attempt: invalid API key");
+                    return (
+                        StatusCode::UNAUTHORIZED,
+                        Json(serde_json::json!({
+                            "error": "Unauthorized"
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+            None => {
+                warn!("Unauthorized link metrics attempt: missing API key");
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({
+                        "error": "Unauthorized"
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    // Get metrics report
+    let report = LinkMetrics::global().report();
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// Circuit breaker endpoint (API key protected) - returns the state of each dependency's breaker
+async fn circuit_breakers_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    // Check API key with constant-time comparison
+    if let Some(expected_key) = &state.config.api_key {
+        match headers.get("X-API-Key") {
+            Some(header_value) => {
+                let provided_key = header_value.to_str().unwrap_or("");
+                if !security::constant_time_compare(provided_key, expected_key) {
+                    warn!("Unauthorized circuit breakers attempt: invalid API key");
+                    return (
+                        StatusCode::UNAUTHORIZED,
+                        Json(serde_json::json!({
+                            "error": "Unauthorized"
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+            None => {
+                warn!("Unauthorized circuit breakers attempt: missing API key");
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({
+                        "error": "Unauthorized"
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let report = CircuitBreakers::global().report();
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// Deliveries endpoint (API key protected) - returns the progress of the recent digest and broadcast fan-outs
+async fn deliveries_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    // Check API key with constant-time comparison
+    if let Some(expected_key) = &state.config.api_key {
+        match headers.get("X-API-Key") {
+            Some(header_value) => {
+                let provided_key = header_value.to_str().unwrap_or("");
+                if !security::constant_time_compare(provided_key, expected_key) {
+                    warn!("Unauthorized deliveries attempt: invalid API key");
+                    return (
+                        StatusCode::UNAUTHORIZED,
+                        Json(serde_json::json!({
+                            "error": "Unauthorized"
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+            None => {
+                warn!("Unauthorized deliveries attempt: missing API key");
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({
+                        "error": "Unauthorized"
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let report = telegram::FanOuts::global().report();
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// Resume delivery endpoint (API key protected) - sends a summary to the subscribers
+/// its delivery ledger lists as pending or failed, then returns the ledger counts
+async fn resume_delivery_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<ResumeParams>,
+) -> impl IntoResponse {
+    // Check API key with constant-time comparison
+    if let Some(expected_key) = &state.config.api_key {
+        match headers.get("X-API-Key") {
+            Some(header_value) => {
+                let provided_key = header_value.to_str().unwrap_or("");
+                if !security::constant_time_compare(provided_key, expected_key) {
+                    warn!("Unauthorized resume delivery attempt: invalid API key");
+                    return (
+                        StatusCode::UNAUTHORIZED,
+                        Json(serde_json::json!({
+                            "error": "Unauthorized"
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+            None => {
+                warn!("Unauthorized resume delivery attempt: missing API key");
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({
+                        "error": "Unauthorized"
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let summary = match state.db.get_summary(params.summary_id).await {
+        Ok(Some(summary)) => summary,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Unknown summary"
+                })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            warn!("Failed to load summary {}: {}", params.summary_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Error: {}", e)
+                })),
+            )
+                .into_response();
+        }
+    };
+    let Some(topic) = topics::find_topic(&state.config.topics, &summary.digest_id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Unknown digest"
+            })),
+        )
+            .into_response();
+    };
+
+    info!("Resuming delivery of summary {}", summary.id);
+    let resumed = async {
+        telegram::resume_delivery(
+            &state.config.for_topic(topic),
+            &state.db,
+            &state.telegram,
+            &summary.content,
+            summary.id,
+        )
+        .await?;
+        state.db.get_delivery_counts(summary.id).await
+    };
+
+    match resumed.await {
+        Ok(counts) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "summary_id": summary.id,
+                "digest": summary.digest_id,
+                "deliveries": counts
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!(
+                "Resuming delivery of summary {} failed: {:?}",
+                summary.id, e
+            );
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Error: {}", e)
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// OpenAI usage endpoint (API key protected) - returns token and cost totals per day or month
+async fn usage_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<UsageParams>,
+) -> impl IntoResponse {
+    // Check API key with constant-time comparison
+    if let Some(expected_key) = &state.config.api_key {
+        match headers.get("X-API-Key") {
+            Some(header_value) => {
+                let provided_key = header_value.to_str().unwrap_or("");
+                if !security::constant_time_compare(provided_key, expected_key) {
+                    warn!("Unauthorized usage attempt: invalid API key");
+                    return (
+                        StatusCode::UNAUTHORIZED,
+                        Json(serde_json::json!({
+                            "error": "Unauthorized"
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+            None => {
+                warn!("Unauthorized usage attempt: missing API key");
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({
+                        "error": "Unauthorized"
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let (period, default_limit) = match params.period.as_deref() {
+        None | Some("day") => (db::UsagePeriod::Day, 30),
+        Some("month") => (db::UsagePeriod::Month, 12),
+        Some(other) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Invalid period '{}': expected 'day' or 'month'", other)
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match state
+        .db
+        .get_usage_totals(period, params.limit.unwrap_or(default_limit))
+        .await
+    {
+        Ok(totals) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "totals": totals,
+                "month_to_date_usd": UsageTracker::global().month_to_date_cost(),
+                "monthly_budget_usd": state.config.openai_monthly_budget_usd
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("Failed to fetch usage totals: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Error: {}", e)
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Reader ratings endpoint (API key protected) - returns average digest ratings
+/// per digest, model and prompt version, plus the latest comments
+async fn ratings_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<RatingsParams>,
+) -> impl IntoResponse {
+    // Check API key with constant-time comparison
+    if let Some(expected_key) = &state.config.api_key {
+        match headers.get("X-API-Key") {
+            Some(header_value) => {
+                let provided_key = header_value.to_str().unwrap_or("");
+                if !security::constant_time_compare(provided_key, expected_key) {
+                    warn!("Unauthorized ratings attempt: invalid API key");
+                    return (
+                        StatusCode::UNAUTHORIZED,
+                        Json(serde_json::json!({
+                            "error": "Unauthorized"
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+            None => {
+                warn!("Unauthorized ratings attempt: missing API key");
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(serde_json::json!({
+                        "error": "Unauthorized"
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let days = params.days.unwrap_or(30);
+    let report = async {
+        let totals = state.db.get_rating_totals(days).await?;
+        let comments = state.db.get_recent_rating_comments(20).await?;
+        anyhow::Ok((totals, comments))
+    };
+
+    match report.await {
+        Ok((totals, comments)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "days": days.max(1),
+                "totals": totals,
+                "recent_comments": comments
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("Failed to fetch rating report: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Error: {}", e)
+                })),
+            )
+                .into_response()
+        }
+    }
+}
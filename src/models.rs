@@ -0,0 +1,405 @@
+//! Model capability registry.
+//!
+//! Describes what each chat model supports so request builders don't guess from
+//! the model name: context window, maximum output, whether `temperature` and
+//! `reasoning_effort` are accepted, and list prices (used by [`crate::usage`]).
+//!
+//! The built-in entries cover the OpenAI models this project has been run
+//! with. `MODELS_FILE` points at a JSON array of entries that are added to (or
+//! replace) the built-ins, e.g. for a model on another OpenAI-compatible
+//! endpoint:
+//!
+//! ```json
+//! [{"name": "llama-3.3-70b", "context_window": 131072, "max_output_tokens": 32768,
+//!   "supports_temperature": true, "supports_reasoning_effort": false,
+//!   "input_price_per_million": 0.59, "output_price_per_million": 0.79}]
+//! ```
+//!
+//! Dated snapshots ("gpt-5-mini-2025-08-07", "gpt-4-0613") resolve to their
+//! base name. Models that match no entry are rejected at startup.
+
+use crate::usage::ModelPrice;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Minimum completion budget for reasoning models: hidden reasoning tokens
+/// count against `max_completion_tokens` along with the visible output.
+pub const REASONING_MIN_COMPLETION_TOKENS: u32 = 16000;
+
+/// Smallest prompt budget a model and completion budget must leave: room for
+/// the system prompt and a useful batch of tweets.
+pub const MIN_PROMPT_TOKENS: u32 = 2000;
+
+/// Capabilities and prices of one model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelSpec {
+    pub name: String,
+    /// Total tokens (prompt + completion) the model accepts
+    pub context_window: u32,
+    /// Largest accepted `max_completion_tokens`
+    pub max_output_tokens: u32,
+    /// Whether a custom `temperature` is accepted (reasoning models reject it)
+    pub supports_temperature: bool,
+    /// Whether `reasoning_effort` is accepted
+    pub supports_reasoning_effort: bool,
+    /// USD per 1M prompt tokens
+    #[serde(default)]
+    pub input_price_per_million: Option<f64>,
+    /// USD per 1M completion tokens
+    #[serde(default)]
+    pub output_price_per_million: Option<f64>,
+}
+
+impl ModelSpec {
+    /// Temperature and reasoning effort to send (unsupported parameters are omitted)
+    pub fn sampling_params(&self, temperature: f32) -> (Option<f32>, Option<String>) {
+        (
+            self.supports_temperature.then_some(temperature),
+            self.supports_reasoning_effort.then(|| "low".to_string()),
+        )
+    }
+
+    /// `max_completion_tokens` for a request: the requested budget, raised to
+    /// [`REASONING_MIN_COMPLETION_TOKENS`] for reasoning models and capped at
+    /// the model's maximum output.
+    pub fn completion_tokens(&self, requested: u32) -> u32 {
+        let budget = if self.supports_reasoning_effort {
+            requested.max(REASONING_MIN_COMPLETION_TOKENS)
+        } else {
+            requested
+        };
+        budget.min(self.max_output_tokens)
+    }
+
+    /// Completion budget when none is configured: `preferred`, capped at the
+    /// model's maximum output and at a quarter of the context window so the
+    /// prompt keeps most of it
+    pub fn default_completion_budget(&self, preferred: u32) -> u32 {
+        preferred
+            .min(self.max_output_tokens)
+            .min(self.context_window / 4)
+    }
+
+    /// Prompt tokens left in the context window after the completion budget
+    pub fn input_budget(&self, requested_completion: u32) -> u32 {
+        self.context_window
+            .saturating_sub(self.completion_tokens(requested_completion))
+    }
+
+    /// List price, if both input and output prices are known
+    pub fn price(&self) -> Option<ModelPrice> {
+        Some(ModelPrice {
+            input_per_million: self.input_price_per_million?,
+            output_per_million: self.output_price_per_million?,
+        })
+    }
+}
+
+/// Built-in models: (name, context window, max output, temperature, reasoning effort,
+/// input and output USD per 1M tokens)
+const BUILTIN_MODELS: [(&str, u32, u32, bool, bool, f64, f64); 16] = [
+    ("gpt-5", 400_000, 128_000, false, true, 1.25, 10.00),
+    ("gpt-5-mini", 400_000, 128_000, false, true, 0.25, 2.00),
+    ("gpt-5-nano", 400_000, 128_000, false, true, 0.05, 0.40),
+    ("gpt-4.1", 1_047_576, 32_768, true, false, 2.00, 8.00),
+    ("gpt-4.1-mini", 1_047_576, 32_768, true, false, 0.40, 1.60),
+    ("gpt-4o", 128_000, 16_384, true, false, 2.50, 10.00),
+    ("gpt-4o-mini", 128_000, 16_384, true, false, 0.15, 0.60),
+    ("gpt-4-turbo", 128_000, 4_096, true, false, 10.00, 30.00),
+    ("gpt-4", 8_192, 8_192, true, false, 30.00, 60.00),
+    ("gpt-3.5-turbo", 16_385, 4_096, true, false, 0.50, 1.50),
+    ("o1", 200_000, 100_000, false, true, 15.00, 60.00),
+    // Early o1 models reject both temperature and reasoning_effort
+    ("o1-mini", 128_000, 65_536, false, false, 1.10, 4.40),
+    ("o1-preview", 128_000, 32_768, false, false, 15.00, 60.00),
+    ("o3", 200_000, 100_000, false, true, 2.00, 8.00),
+    ("o3-mini", 200_000, 100_000, false, true, 1.10, 4.40),
+    ("o4-mini", 200_000, 100_000, false, true, 1.10, 4.40),
+];
+
+/// Known models by name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRegistry {
+    models: HashMap<String, ModelSpec>,
+}
+
+impl Default for ModelRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ModelRegistry {
+    /// The built-in models
+    pub fn builtin() -> Self {
+        let models = BUILTIN_MODELS
+            .iter()
+            .map(
+                |&(
+                    name,
+                    context_window,
+                    max_output_tokens,
+                    temperature,
+                    reasoning,
+                    input,
+                    output,
+                )| {
+                    (
+                        name.to_string(),
+                        ModelSpec {
+                            name: name.to_string(),
+                            context_window,
+                            max_output_tokens,
+                            supports_temperature: temperature,
+                            supports_reasoning_effort: reasoning,
+                            input_price_per_million: Some(input),
+                            output_price_per_million: Some(output),
+                        },
+                    )
+                },
+            )
+            .collect();
+        Self { models }
+    }
+
+    /// Add or replace entries from a JSON array of [`ModelSpec`]s
+    pub fn with_overrides_json(mut self, json: &str) -> Result<Self> {
+        let specs: Vec<ModelSpec> =
+            serde_json::from_str(json).context("Models file must be a JSON array of models")?;
+        for spec in specs {
+            if spec.name.trim().is_empty() {
+                anyhow::bail!("Model entry has no name");
+            }
+            if spec.max_output_tokens == 0 || spec.max_output_tokens > spec.context_window {
+                anyhow::bail!(
+                    "Model '{}': max_output_tokens must be between 1 and context_window",
+                    spec.name
+                );
+            }
+            self.models.insert(spec.name.clone(), spec);
+        }
+        Ok(self)
+    }
+
+    /// Built-in models plus the entries in a models file
+    pub fn load(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read models file {}", path))?;
+        Self::builtin()
+            .with_overrides_json(&json)
+            .with_context(|| format!("Invalid models file {}", path))
+    }
+
+    /// Spec for a model; dated snapshots ("-2025-08-07", "-0613") use their
+    /// base name. Other variants ("-chat-latest", "-preview") can differ in
+    /// what they accept, so they need entries of their own.
+    pub fn get(&self, model: &str) -> Option<&ModelSpec> {
+        if let Some(spec) = self.models.get(model) {
+            return Some(spec);
+        }
+        self.models
+            .iter()
+            .filter(|(name, _)| {
+                model
+                    .strip_prefix(name.as_str())
+                    .and_then(|rest| rest.strip_prefix('-'))
+                    .is_some_and(is_snapshot_date)
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, spec)| spec)
+    }
+
+    /// Spec for a model, or an error naming the model for startup validation
+    pub fn require(&self, model: &str) -> Result<&ModelSpec> {
+        self.get(model).with_context(|| {
+            format!(
+                "Unknown model '{}': add its capabilities to MODELS_FILE (see README)",
+                model
+            )
+        })
+    }
+
+    /// Spec for a model, falling back to a conservative non-reasoning profile
+    /// for models that slipped past startup validation (e.g. test configs)
+    pub fn resolve(&self, model: &str) -> ModelSpec {
+        self.get(model).cloned().unwrap_or_else(|| ModelSpec {
+            name: model.to_string(),
+            context_window: 16_385,
+            max_output_tokens: 4_096,
+            supports_temperature: true,
+            supports_reasoning_effort: false,
+            input_price_per_million: None,
+            output_price_per_million: None,
+        })
+    }
+
+    /// All models, for building the price table
+    pub fn iter(&self) -> impl Iterator<Item = &ModelSpec> {
+        self.models.values()
+    }
+}
+
+/// A snapshot date suffix: "YYYY-MM-DD" or "MMDD"
+fn is_snapshot_date(suffix: &str) -> bool {
+    let digits =
+        |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    match suffix.split('-').collect::<Vec<_>>().as_slice() {
+        [year, month, day] => digits(year, 4) && digits(month, 2) && digits(day, 2),
+        [short] => digits(short, 4),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Lookup Tests ====================
+
+    #[test]
+    fn test_builtin_lookup_and_snapshots() {
+        let registry = ModelRegistry::builtin();
+
+        assert_eq!(registry.get("gpt-5-mini").unwrap().name, "gpt-5-mini");
+        assert_eq!(
+            registry.get("gpt-5-mini-2025-08-07").unwrap().name,
+            "gpt-5-mini"
+        );
+        assert_eq!(registry.get("gpt-4-0613").unwrap().name, "gpt-4");
+        assert_eq!(registry.get("gpt-4o-mini").unwrap().name, "gpt-4o-mini");
+        // Variants that accept different parameters aren't snapshots
+        assert!(registry.get("gpt-5-chat-latest").is_none());
+        assert!(registry.get("o1-pro").is_none());
+        assert_eq!(registry.get("o1-preview").unwrap().name, "o1-preview");
+        assert_eq!(registry.get("o1-mini-2024-09-12").unwrap().name, "o1-mini");
+        assert!(registry.get("gpt-5-mini-2025-08").is_none());
+        assert!(registry.get("gpt-5x").is_none());
+        assert!(registry.get("llama-3.3-70b").is_none());
+    }
+
+    #[test]
+    fn test_require_rejects_unknown_model() {
+        let err = ModelRegistry::builtin().require("gpt-9").unwrap_err();
+        assert!(err.to_string().contains("Unknown model 'gpt-9'"));
+        assert!(err.to_string().contains("MODELS_FILE"));
+    }
+
+    // ==================== Parameter Tests ====================
+
+    #[test]
+    fn test_sampling_params() {
+        let registry = ModelRegistry::builtin();
+
+        assert_eq!(
+            registry.resolve("gpt-5-nano").sampling_params(0.7),
+            (None, Some("low".to_string()))
+        );
+        assert_eq!(
+            registry.resolve("gpt-4o-mini").sampling_params(0.7),
+            (Some(0.7), None)
+        );
+    }
+
+    #[test]
+    fn test_completion_tokens() {
+        let registry = ModelRegistry::builtin();
+
+        // Reasoning models get room for hidden reasoning
+        assert_eq!(
+            registry.resolve("gpt-5-mini").completion_tokens(2500),
+            16000
+        );
+        assert_eq!(
+            registry.resolve("gpt-5-mini").completion_tokens(40000),
+            40000
+        );
+        // Non-reasoning models get the requested budget, capped at max output
+        assert_eq!(
+            registry.resolve("gpt-4o-mini").completion_tokens(2500),
+            2500
+        );
+        assert_eq!(
+            registry.resolve("gpt-4-turbo").completion_tokens(16000),
+            4096
+        );
+        assert_eq!(registry.resolve("gpt-4").input_budget(2500), 8192 - 2500);
+    }
+
+    #[test]
+    fn test_resolve_unknown_model_is_conservative() {
+        let spec = ModelRegistry::builtin().resolve("mystery");
+        assert!(spec.supports_temperature);
+        assert!(!spec.supports_reasoning_effort);
+        assert_eq!(spec.completion_tokens(16000), 4096);
+        assert!(spec.price().is_none());
+    }
+
+    // ==================== Override Tests ====================
+
+    #[test]
+    fn test_overrides_add_and_replace_models() {
+        let registry = ModelRegistry::builtin()
+            .with_overrides_json(
+                r#"[
+                    {"name": "llama-3.3-70b", "context_window": 131072, "max_output_tokens": 32768,
+                     "supports_temperature": true, "supports_reasoning_effort": false},
+                    {"name": "gpt-4o-mini", "context_window": 128000, "max_output_tokens": 8000,
+                     "supports_temperature": true, "supports_reasoning_effort": false,
+                     "input_price_per_million": 0.1, "output_price_per_million": 0.4}
+                ]"#,
+            )
+            .unwrap();
+
+        let llama = registry.get("llama-3.3-70b").unwrap();
+        assert_eq!(llama.max_output_tokens, 32768);
+        assert!(llama.price().is_none());
+
+        let mini = registry.get("gpt-4o-mini").unwrap();
+        assert_eq!(mini.max_output_tokens, 8000);
+        assert_eq!(mini.price().unwrap().input_per_million, 0.1);
+    }
+
+    #[test]
+    fn test_overrides_reject_invalid_entries() {
+        let cases = [
+            ("{}", "JSON array"),
+            (
+                r#"[{"name": "x", "context_window": 1000, "max_output_tokens": 2000,
+                     "supports_temperature": true, "supports_reasoning_effort": false}]"#,
+                "max_output_tokens",
+            ),
+            (
+                r#"[{"name": " ", "context_window": 1000, "max_output_tokens": 100,
+                     "supports_temperature": true, "supports_reasoning_effort": false}]"#,
+                "no name",
+            ),
+            (r#"[{"name": "x"}]"#, "JSON array"),
+        ];
+        for (json, expected) in cases {
+            let err = ModelRegistry::builtin()
+                .with_overrides_json(json)
+                .unwrap_err();
+            assert!(
+                format!("{:#}", err).contains(expected),
+                "{}: {:#}",
+                json,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_example_models_file_is_valid() {
+        let registry = ModelRegistry::builtin()
+            .with_overrides_json(include_str!("../data/models.example.json"))
+            .unwrap();
+        assert!(registry.get("llama-3.3-70b-versatile").is_some());
+    }
+
+    #[test]
+    fn test_load_missing_file_fails() {
+        let err = ModelRegistry::load("/nonexistent/models.json").unwrap_err();
+        assert!(err.to_string().contains("Failed to read models file"));
+    }
+}
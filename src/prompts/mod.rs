@@ -0,0 +1,696 @@
+//! Prompt templates loaded from files.
+//!
+//! Every prompt sent to OpenAI is rendered from a named template with `{{variable}}`
+//! placeholders. The built-in defaults are compiled in from `src/prompts/defaults/`;
+//! a file named `<template>.md` in `PROMPTS_DIR` replaces the built-in template of
+//! the same name, so wording can change without a rebuild. Missing files fall back
+//! to the built-ins, and files that use unknown placeholders (or drop required
+//! ones) are rejected with a warning.
+//!
+//! Each template set has a version (a content hash) that is stored with every
+//! saved summary and translation, so a digest can be traced back to its prompt.
+
+use crate::topics::DigestTopic;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+/// Summarization system prompt (`max_words`, `tier_lists`)
+pub const SUMMARY_SYSTEM: &str = "summary_system";
+/// Account tier lists inserted into the summarization system prompt
+pub const SUMMARY_TIERS: &str = "summary_tiers";
+/// Summarization user prompt (`tweet_count`, `tweets`)
+pub const SUMMARY_USER: &str = "summary_user";
+/// Stories from previous digests appended to the summarization user prompt (`covered`)
+pub const SUMMARY_CONTINUITY: &str = "summary_continuity";
+/// Output-format override appended for structured (JSON) digests
+pub const DIGEST_JSON: &str = "digest_json";
+/// Translation system prompt (`target_language`, `section_headers`, `examples`)
+pub const TRANSLATION_SYSTEM: &str = "translation_system";
+/// Translation user prompt (`target_language`, `summary`)
+pub const TRANSLATION_USER: &str = "translation_user";
+/// Extra instructions for translating a structured digest
+pub const TRANSLATION_DIGEST: &str = "translation_digest";
+/// Condensing system prompt (`max_chars`)
+pub const CONDENSE_SYSTEM: &str = "condense_system";
+/// Prefix of per-language translation examples, e.g. "translation_examples_es"
+pub const TRANSLATION_EXAMPLES_PREFIX: &str = "translation_examples_";
+
+/// A template name with its built-in text and the placeholders it may use
+struct TemplateSpec {
+    name: &'static str,
+    builtin: &'static str,
+    variables: &'static [&'static str],
+    /// Placeholders an override must keep (the prompt is useless without them)
+    required: &'static [&'static str],
+}
+
+const TEMPLATE_SPECS: [TemplateSpec; 9] = [
+    TemplateSpec {
+        name: SUMMARY_SYSTEM,
+        builtin: include_str!("defaults/summary_system.md"),
+        variables: &["max_words", "tier_lists", "persona", "sections"],
+        required: &[],
+    },
+    TemplateSpec {
+        name: SUMMARY_TIERS,
+        builtin: include_str!("defaults/summary_tiers.md"),
+        variables: &[],
+        required: &[],
+    },
+    TemplateSpec {
+        name: SUMMARY_USER,
+        builtin: include_str!("defaults/summary_user.md"),
+        variables: &["tweet_count", "tweets", "audience"],
+        required: &["tweets"],
+    },
+    TemplateSpec {
+        name: SUMMARY_CONTINUITY,
+        builtin: include_str!("defaults/summary_continuity.md"),
+        variables: &["covered"],
+        required: &["covered"],
+    },
+    TemplateSpec {
+        name: DIGEST_JSON,
+        builtin: include_str!("defaults/digest_json.md"),
+        variables: &[],
+        required: &[],
+    },
+    TemplateSpec {
+        name: TRANSLATION_SYSTEM,
+        builtin: include_str!("defaults/translation_system.md"),
+        variables: &["target_language", "section_headers", "examples"],
+        required: &["target_language"],
+    },
+    TemplateSpec {
+        name: TRANSLATION_USER,
+        builtin: include_str!("defaults/translation_user.md"),
+        variables: &["target_language", "summary"],
+        required: &["summary"],
+    },
+    TemplateSpec {
+        name: TRANSLATION_DIGEST,
+        builtin: include_str!("defaults/translation_digest.md"),
+        variables: &[],
+        required: &[],
+    },
+    TemplateSpec {
+        name: CONDENSE_SYSTEM,
+        builtin: include_str!("defaults/condense_system.md"),
+        variables: &["max_chars"],
+        required: &[],
+    },
+];
+
+/// Built-in translation examples per language code
+const BUILTIN_EXAMPLES: [(&str, &str); 1] =
+    [("es", include_str!("defaults/translation_examples_es.md"))];
+
+static PLACEHOLDER_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn placeholder_regex() -> &'static Regex {
+    PLACEHOLDER_REGEX.get_or_init(|| Regex::new(r"\{\{\s*([a-z_]+)\s*\}\}").unwrap())
+}
+
+/// Where a template's text came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSource {
+    Builtin,
+    File(PathBuf),
+}
+
+/// A named prompt template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    name: String,
+    body: String,
+    source: TemplateSource,
+}
+
+impl PromptTemplate {
+    fn new(name: &str, text: &str, source: TemplateSource) -> Self {
+        Self {
+            name: name.to_string(),
+            body: text.trim_end().to_string(),
+            source,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn source(&self) -> &TemplateSource {
+        &self.source
+    }
+
+    /// Placeholder names used by the template, in order of first use
+    pub fn placeholders(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for caps in placeholder_regex().captures_iter(&self.body) {
+            let name = caps.get(1).unwrap().as_str();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Substitute `{{name}}` placeholders in a single pass (values are never
+    /// re-scanned, so tweet text containing braces is safe). Unknown
+    /// placeholders are left as-is.
+    pub fn render(&self, vars: &[(&str, &str)]) -> String {
+        placeholder_regex()
+            .replace_all(&self.body, |caps: &regex::Captures| {
+                let name = caps.get(1).unwrap().as_str();
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+                    .unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+}
+
+/// The full set of prompt templates used by summarization and translation.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PromptLibrary {
+    templates: BTreeMap<String, PromptTemplate>,
+}
+
+impl Default for PromptLibrary {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl fmt::Debug for PromptLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let overrides: Vec<&str> = self
+            .templates
+            .values()
+            .filter(|t| t.source != TemplateSource::Builtin)
+            .map(|t| t.name.as_str())
+            .collect();
+        f.debug_struct("PromptLibrary")
+            .field("summary_version", &self.summary_version())
+            .field("overrides", &overrides)
+            .finish()
+    }
+}
+
+impl PromptLibrary {
+    /// The compiled-in default templates
+    pub fn builtin() -> Self {
+        let mut templates = BTreeMap::new();
+        for spec in &TEMPLATE_SPECS {
+            templates.insert(
+                spec.name.to_string(),
+                PromptTemplate::new(spec.name, spec.builtin, TemplateSource::Builtin),
+            );
+        }
+        for (code, text) in BUILTIN_EXAMPLES {
+            let name = format!("{}{}", TRANSLATION_EXAMPLES_PREFIX, code);
+            templates.insert(
+                name.clone(),
+                PromptTemplate::new(&name, text, TemplateSource::Builtin),
+            );
+        }
+        Self { templates }
+    }
+
+    /// Load templates from `dir`, using the built-in default for every template
+    /// without a (valid) `<name>.md` file. A missing directory is not an error.
+    pub fn load(dir: impl AsRef<Path>) -> Self {
+        Self::builtin().with_overrides(dir)
+    }
+
+    /// Override this library's templates with the valid `<name>.md` files in
+    /// `dir`; templates without a file keep their current text. Used to layer a
+    /// digest's own prompt directory over the shared one.
+    pub fn with_overrides(self, dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref();
+        let mut library = self;
+
+        if !dir.is_dir() {
+            info!(
+                "Prompt directory {} not found, keeping current prompts",
+                dir.display()
+            );
+            return library;
+        }
+
+        for spec in &TEMPLATE_SPECS {
+            let path = dir.join(format!("{}.md", spec.name));
+            if let Some(template) = read_override(spec.name, &path, spec.variables, spec.required) {
+                library.templates.insert(spec.name.to_string(), template);
+            }
+        }
+
+        // Translation examples are optional and keyed by language code
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("Failed to list prompt directory {}: {}", dir.display(), e);
+                return library;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".md"))
+            else {
+                continue;
+            };
+            let Some(code) = name.strip_prefix(TRANSLATION_EXAMPLES_PREFIX) else {
+                continue;
+            };
+            if code.is_empty() || !code.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+                warn!(
+                    "Ignoring prompt file with invalid language code: {}",
+                    path.display()
+                );
+                continue;
+            }
+            if let Some(template) = read_override(name, &path, &[], &[]) {
+                library.templates.insert(name.to_string(), template);
+            }
+        }
+
+        let overrides = library
+            .templates
+            .values()
+            .filter(|t| t.source != TemplateSource::Builtin)
+            .count();
+        info!(
+            "✓ Loaded prompts from {} ({} overrides, summary version {})",
+            dir.display(),
+            overrides,
+            library.summary_version()
+        );
+        library
+    }
+
+    /// Get a template by name
+    pub fn get(&self, name: &str) -> Option<&PromptTemplate> {
+        self.templates.get(name)
+    }
+
+    fn render(&self, name: &str, vars: &[(&str, &str)]) -> String {
+        self.templates
+            .get(name)
+            .map(|t| t.render(vars))
+            .unwrap_or_default()
+    }
+
+    /// Summarization system prompt with the topic's persona and sections
+    pub fn summary_system_prompt(&self, topic: &DigestTopic, max_words: u32) -> String {
+        let tier_lists = self.render(SUMMARY_TIERS, &[]);
+        self.render(
+            SUMMARY_SYSTEM,
+            &[
+                ("max_words", &max_words.to_string()),
+                ("tier_lists", &tier_lists),
+                ("persona", &topic.persona),
+                ("sections", &topic.section_list()),
+            ],
+        )
+    }
+
+    /// Summarization user prompt with the formatted tweets
+    pub fn summary_user_prompt(
+        &self,
+        topic: &DigestTopic,
+        tweet_count: usize,
+        tweets: &str,
+    ) -> String {
+        self.render(
+            SUMMARY_USER,
+            &[
+                ("tweet_count", &tweet_count.to_string()),
+                ("tweets", tweets),
+                ("audience", &topic.audience),
+            ],
+        )
+    }
+
+    /// "Already covered" context listing stories from previous digests
+    pub fn summary_continuity_prompt(&self, covered: &str) -> String {
+        self.render(SUMMARY_CONTINUITY, &[("covered", covered)])
+    }
+
+    /// Output-format instructions for structured digests
+    pub fn digest_json_instructions(&self) -> String {
+        self.render(DIGEST_JSON, &[])
+    }
+
+    /// Translation system prompt; `section_headers` and `examples` are inserted verbatim
+    pub fn translation_system_prompt(
+        &self,
+        target_language: &str,
+        section_headers: &str,
+        examples: &str,
+    ) -> String {
+        self.render(
+            TRANSLATION_SYSTEM,
+            &[
+                ("target_language", target_language),
+                ("section_headers", section_headers),
+                ("examples", examples),
+            ],
+        )
+    }
+
+    /// Translation examples for a language, if any are defined
+    pub fn translation_examples(&self, language_code: &str) -> Option<&str> {
+        self.get(&format!("{}{}", TRANSLATION_EXAMPLES_PREFIX, language_code))
+            .map(|t| t.body())
+            .filter(|body| !body.is_empty())
+    }
+
+    /// Translation user prompt
+    pub fn translation_user_prompt(&self, target_language: &str, summary: &str) -> String {
+        self.render(
+            TRANSLATION_USER,
+            &[("target_language", target_language), ("summary", summary)],
+        )
+    }
+
+    /// Extra instructions for translating a structured digest
+    pub fn translation_digest_instructions(&self) -> String {
+        self.render(TRANSLATION_DIGEST, &[])
+    }
+
+    /// Condensing system prompt
+    pub fn condense_system_prompt(&self, max_chars: usize) -> String {
+        self.render(CONDENSE_SYSTEM, &[("max_chars", &max_chars.to_string())])
+    }
+
+    /// Version of the templates that produce summaries
+    pub fn summary_version(&self) -> String {
+        self.fingerprint(&[
+            SUMMARY_SYSTEM,
+            SUMMARY_TIERS,
+            SUMMARY_USER,
+            SUMMARY_CONTINUITY,
+            DIGEST_JSON,
+        ])
+    }
+
+    /// Version of the templates that produce translations into `language_code`
+    pub fn translation_version(&self, language_code: &str) -> String {
+        let examples = format!("{}{}", TRANSLATION_EXAMPLES_PREFIX, language_code);
+        self.fingerprint(&[
+            TRANSLATION_SYSTEM,
+            TRANSLATION_USER,
+            TRANSLATION_DIGEST,
+            &examples,
+        ])
+    }
+
+    /// Stable 64-bit FNV-1a hash over the named templates (missing ones count as empty)
+    fn fingerprint(&self, names: &[&str]) -> String {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let mut hash = OFFSET;
+        for name in names {
+            let body = self.get(name).map(|t| t.body()).unwrap_or("");
+            for byte in name.bytes().chain([0]).chain(body.bytes()).chain([0]) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(PRIME);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+/// Read and validate one override file; None means "keep the built-in"
+fn read_override(
+    name: &str,
+    path: &Path,
+    variables: &[&str],
+    required: &[&str],
+) -> Option<PromptTemplate> {
+    if !path.is_file() {
+        return None;
+    }
+
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            warn!(
+                "Failed to read prompt {} ({}), using built-in: {}",
+                name,
+                path.display(),
+                e
+            );
+            return None;
+        }
+    };
+
+    let template = PromptTemplate::new(name, &text, TemplateSource::File(path.to_path_buf()));
+    if template.body.is_empty() {
+        warn!("Prompt {} is empty, using built-in", path.display());
+        return None;
+    }
+
+    let placeholders = template.placeholders();
+    let unknown: Vec<&str> = placeholders
+        .iter()
+        .copied()
+        .filter(|p| !variables.contains(p))
+        .collect();
+    let missing: Vec<&str> = required
+        .iter()
+        .copied()
+        .filter(|r| !placeholders.contains(r))
+        .collect();
+
+    if !unknown.is_empty() || !missing.is_empty() {
+        warn!(
+            "Prompt {} is invalid (unknown placeholders: {:?}, missing: {:?}), using built-in",
+            path.display(),
+            unknown,
+            missing
+        );
+        return None;
+    }
+
+    Some(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    // ==================== Rendering Tests ====================
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let template = PromptTemplate::new(
+            "t",
+            "Hello {{name}}, {{ name }} has {{count}} items",
+            TemplateSource::Builtin,
+        );
+        assert_eq!(
+            template.render(&[("name", "Ana"), ("count", "3")]),
+            "Hello Ana, Ana has 3 items"
+        );
+        assert_eq!(template.placeholders(), vec!["name", "count"]);
+    }
+
+    #[test]
+    fn test_render_does_not_rescan_values() {
+        let template = PromptTemplate::new("t", "{{tweets}} / {{count}}", TemplateSource::Builtin);
+        assert_eq!(
+            template.render(&[("tweets", "literal {{count}}"), ("count", "2")]),
+            "literal {{count}} / 2"
+        );
+    }
+
+    #[test]
+    fn test_render_leaves_unknown_placeholders() {
+        let template = PromptTemplate::new("t", "a {{missing}} b", TemplateSource::Builtin);
+        assert_eq!(template.render(&[]), "a {{missing}} b");
+    }
+
+    // ==================== Built-in Template Tests ====================
+
+    #[test]
+    fn test_builtin_templates_only_use_declared_placeholders() {
+        let library = PromptLibrary::builtin();
+        for spec in &TEMPLATE_SPECS {
+            let template = library.get(spec.name).unwrap();
+            for placeholder in template.placeholders() {
+                assert!(
+                    spec.variables.contains(&placeholder),
+                    "{} uses undeclared {{{{{}}}}}",
+                    spec.name,
+                    placeholder
+                );
+            }
+            for required in spec.required {
+                assert!(template.placeholders().contains(required));
+            }
+        }
+    }
+
+    #[test]
+    fn test_summary_system_prompt_is_fully_rendered() {
+        let prompt = PromptLibrary::builtin().summary_system_prompt(&DigestTopic::default(), 650);
+        assert!(prompt.contains("under 650 words"));
+        assert!(prompt.contains("Tier 1 (highest priority"));
+        assert!(prompt.starts_with("You are an AI/ML tech news curator"));
+        assert!(prompt.contains("- 🚀 Releases\n- 🔬 Research\n"));
+        assert!(!prompt.contains("{{"));
+    }
+
+    #[test]
+    fn test_summary_prompts_use_topic_persona_sections_and_audience() {
+        let topic = DigestTopic {
+            id: "rust".to_string(),
+            name: "Rust".to_string(),
+            persona: "a Rust ecosystem news curator".to_string(),
+            audience: "Rust developers".to_string(),
+            sections: vec!["🚀 Releases".to_string(), "🧰 Crates".to_string()],
+            ..DigestTopic::default()
+        };
+        let library = PromptLibrary::builtin();
+
+        let system = library.summary_system_prompt(&topic, 500);
+        assert!(system.starts_with("You are a Rust ecosystem news curator"));
+        assert!(system.contains("- 🚀 Releases\n- 🧰 Crates\n"));
+        assert!(!system.contains("- 🔬 Research"));
+
+        let user = library.summary_user_prompt(&topic, 1, "1. x");
+        assert!(user.contains("Audience: Rust developers"));
+        assert!(!user.contains("AI/ML"));
+    }
+
+    #[test]
+    fn test_builtin_examples_exist_for_spanish_only() {
+        let library = PromptLibrary::builtin();
+        assert!(library
+            .translation_examples("es")
+            .unwrap()
+            .starts_with("## Translation Examples"));
+        assert!(library.translation_examples("fr").is_none());
+    }
+
+    // ==================== Loading Tests ====================
+
+    #[test]
+    fn test_load_missing_directory_uses_builtin() {
+        let library = PromptLibrary::load("/nonexistent/prompts/dir");
+        assert_eq!(library, PromptLibrary::builtin());
+    }
+
+    #[test]
+    fn test_load_overrides_single_template() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("summary_user.md"),
+            "Summarize {{tweet_count}} tweets:\n{{tweets}}\n",
+        )
+        .unwrap();
+
+        let library = PromptLibrary::load(dir.path());
+        assert_eq!(
+            library.summary_user_prompt(&DigestTopic::default(), 2, "1. a\n2. b"),
+            "Summarize 2 tweets:\n1. a\n2. b"
+        );
+        assert_eq!(
+            library.get(SUMMARY_USER).unwrap().source(),
+            &TemplateSource::File(dir.path().join("summary_user.md"))
+        );
+        // Everything else is still built-in
+        assert_eq!(
+            library.get(SUMMARY_SYSTEM),
+            PromptLibrary::builtin().get(SUMMARY_SYSTEM)
+        );
+    }
+
+    #[test]
+    fn test_with_overrides_layers_over_existing_overrides() {
+        let shared = TempDir::new().unwrap();
+        std::fs::write(shared.path().join("summary_tiers.md"), "Tier 1: labs").unwrap();
+        std::fs::write(shared.path().join("condense_system.md"), "Shorten").unwrap();
+        let topic = TempDir::new().unwrap();
+        std::fs::write(topic.path().join("summary_tiers.md"), "Tier 1: crates").unwrap();
+
+        let library = PromptLibrary::load(shared.path()).with_overrides(topic.path());
+        assert_eq!(library.get(SUMMARY_TIERS).unwrap().body(), "Tier 1: crates");
+        assert_eq!(library.get(CONDENSE_SYSTEM).unwrap().body(), "Shorten");
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_or_missing_placeholders() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("summary_system.md"),
+            "Keep it under {{max_wrds}} words",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("translation_user.md"), "Translate it").unwrap();
+        std::fs::write(dir.path().join("condense_system.md"), "   \n").unwrap();
+
+        let library = PromptLibrary::load(dir.path());
+        assert_eq!(library, PromptLibrary::builtin());
+    }
+
+    #[test]
+    fn test_load_translation_examples_for_new_language() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("translation_examples_fr.md"),
+            "## Exemples\n[fil de Burkov](url)",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("translation_examples_FR!.md"), "bad").unwrap();
+
+        let library = PromptLibrary::load(dir.path());
+        assert_eq!(
+            library.translation_examples("fr"),
+            Some("## Exemples\n[fil de Burkov](url)")
+        );
+        assert!(library.get("translation_examples_FR!").is_none());
+    }
+
+    // ==================== Version Tests ====================
+
+    #[test]
+    fn test_versions_are_stable_and_change_with_content() {
+        let builtin = PromptLibrary::builtin();
+        assert_eq!(
+            builtin.summary_version(),
+            PromptLibrary::builtin().summary_version()
+        );
+        assert_eq!(builtin.summary_version().len(), 16);
+
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("summary_tiers.md"), "Tier 1: only labs").unwrap();
+        let custom = PromptLibrary::load(dir.path());
+
+        assert_ne!(custom.summary_version(), builtin.summary_version());
+        // Translation prompts are untouched, so their version is too
+        assert_eq!(
+            custom.translation_version("es"),
+            builtin.translation_version("es")
+        );
+        assert_ne!(
+            builtin.translation_version("es"),
+            builtin.translation_version("fr")
+        );
+    }
+}
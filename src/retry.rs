@@ -1,7 +1,24 @@
+use serde::Serialize;
+use std::collections::HashMap;
 use std::future::Future;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
+
+/// Randomization applied to backoff delays so that many clients retrying the
+/// same failure don't all come back at the same moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// Plain exponential backoff
+    #[default]
+    None,
+    /// Uniformly random between zero and the exponential delay
+    Full,
+    /// Uniformly random between `initial_delay` and three times the previous
+    /// delay, capped at `max_delay`
+    Decorrelated,
+}
 
 /// Configuration for retry behavior
 #[derive(Debug, Clone)]
@@ -14,6 +31,11 @@ pub struct RetryConfig {
     pub max_delay: Duration,
     /// Multiplier for exponential backoff (e.g., 2.0 doubles the delay each time)
     pub backoff_multiplier: f64,
+    /// Randomization applied to the backoff delay
+    pub jitter: Jitter,
+    /// Longest server-requested wait (Retry-After) we are willing to honor;
+    /// a longer hint fails the operation instead of sleeping
+    pub max_retry_after: Duration,
 }
 
 impl RetryConfig {
@@ -24,6 +46,8 @@ impl RetryConfig {
             initial_delay,
             max_delay: Duration::from_secs(30),
             backoff_multiplier: 2.0,
+            jitter: Jitter::None,
+            max_retry_after: Duration::from_secs(60),
         }
     }
 
@@ -39,6 +63,18 @@ impl RetryConfig {
         self
     }
 
+    /// Set the jitter strategy
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the longest server-requested wait to honor
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
     /// Preset: Fast retries for health checks (4 attempts, ~7s total)
     /// Delays: 1s, 2s, 4s = 7s total wait time
     pub fn health_check() -> Self {
@@ -48,11 +84,12 @@ impl RetryConfig {
     }
 
     /// Preset: Standard retries for API calls (3 attempts)
-    /// Delays: 1s, 2s = 3s total wait time
+    /// Delays: 1s, then 1-3s (decorrelated jitter) = 2-4s total wait time
     pub fn api_call() -> Self {
         Self::new(3, Duration::from_secs(1))
             .with_max_delay(Duration::from_secs(5))
             .with_backoff_multiplier(2.0)
+            .with_jitter(Jitter::Decorrelated)
     }
 
     /// Preset: RSS feed retries (3 attempts with shorter delays)
@@ -63,7 +100,7 @@ impl RetryConfig {
             .with_backoff_multiplier(2.0)
     }
 
-    /// Calculate the delay for a given attempt number (0-indexed)
+    /// Calculate the delay for a given attempt number (0-indexed), before jitter
     fn delay_for_attempt(&self, attempt: u32) -> Duration {
         if attempt == 0 {
             return Duration::ZERO;
@@ -75,6 +112,19 @@ impl RetryConfig {
         let delay = Duration::from_millis(delay_ms as u64);
         delay.min(self.max_delay)
     }
+
+    /// Delay before `attempt` (1-indexed retry) with jitter applied.
+    /// `previous` is the delay used before the previous attempt.
+    fn jittered_delay(&self, attempt: u32, previous: Duration) -> Duration {
+        match self.jitter {
+            Jitter::None => self.delay_for_attempt(attempt),
+            Jitter::Full => random_between(Duration::ZERO, self.delay_for_attempt(attempt)),
+            Jitter::Decorrelated => {
+                let upper = (previous * 3).max(self.initial_delay);
+                random_between(self.initial_delay, upper).min(self.max_delay)
+            }
+        }
+    }
 }
 
 impl Default for RetryConfig {
@@ -83,6 +133,36 @@ impl Default for RetryConfig {
     }
 }
 
+/// Uniformly random duration in `[low, high]`
+fn random_between(low: Duration, high: Duration) -> Duration {
+    if high <= low {
+        return low;
+    }
+    let span = (high - low).as_millis() as u64;
+    low + Duration::from_millis(fastrand::u64(0..=span))
+}
+
+/// What a `with_retry_if` predicate decided about an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Fail immediately
+    Stop,
+    /// Retry after the configured backoff
+    Retry,
+    /// Retry after the server-requested delay instead of the backoff
+    RetryAfter(Duration),
+}
+
+impl From<bool> for RetryDecision {
+    fn from(retry: bool) -> Self {
+        if retry {
+            Self::Retry
+        } else {
+            Self::Stop
+        }
+    }
+}
+
 /// Execute an async operation with retries
 ///
 /// # Arguments
@@ -98,79 +178,26 @@ impl Default for RetryConfig {
 pub async fn with_retry<T, E, F, Fut>(
     config: &RetryConfig,
     operation_name: &str,
-    mut operation: F,
+    operation: F,
 ) -> Result<T, E>
 where
     F: FnMut() -> Fut,
     Fut: Future<Output = Result<T, E>>,
     E: std::fmt::Display,
 {
-    assert!(
-        config.max_attempts >= 1,
-        "RetryConfig.max_attempts must be >= 1, got {}",
-        config.max_attempts
-    );
-
-    let mut last_error: Option<E> = None;
-
-    for attempt in 0..config.max_attempts {
-        // Wait before retry (except for first attempt)
-        let delay = config.delay_for_attempt(attempt);
-        if !delay.is_zero() {
-            debug!(
-                "{}: Retry attempt {}/{} after {:?}",
-                operation_name,
-                attempt + 1,
-                config.max_attempts,
-                delay
-            );
-            sleep(delay).await;
-        }
-
-        match operation().await {
-            Ok(result) => {
-                if attempt > 0 {
-                    debug!(
-                        "{}: Succeeded on attempt {}/{}",
-                        operation_name,
-                        attempt + 1,
-                        config.max_attempts
-                    );
-                }
-                return Ok(result);
-            }
-            Err(e) => {
-                let remaining = config.max_attempts - attempt - 1;
-                if remaining > 0 {
-                    warn!(
-                        "{}: Attempt {}/{} failed ({}), {} retries remaining",
-                        operation_name,
-                        attempt + 1,
-                        config.max_attempts,
-                        e,
-                        remaining
-                    );
-                } else {
-                    warn!(
-                        "{}: All {} attempts failed. Last error: {}",
-                        operation_name, config.max_attempts, e
-                    );
-                }
-                last_error = Some(e);
-            }
-        }
-    }
-
-    Err(last_error.expect("At least one attempt should have been made"))
+    with_retry_if(config, operation_name, operation, |_| true).await
 }
 
 /// Execute an async operation with retries, using a predicate to determine if retry is appropriate
 ///
 /// Some errors (like 4xx client errors) should not be retried, while others (5xx, network) should.
+/// The predicate returns a `bool` or a [`RetryDecision`]; `RetryDecision::RetryAfter` carries a
+/// server hint (Retry-After, Telegram's `retry_after`) that replaces the backoff delay. Hints
+/// longer than `config.max_retry_after` fail the operation immediately.
 ///
 /// # Panics
 /// Panics if `config.max_attempts` is 0
-pub async fn with_retry_if<T, E, F, Fut, P>(
+pub async fn with_retry_if<T, E, F, Fut, P, D>(
     config: &RetryConfig,
     operation_name: &str,
     mut operation: F,
@@ -180,7 +207,8 @@ where
     F: FnMut() -> Fut,
     Fut: Future<Output = Result<T, E>>,
     E: std::fmt::Display,
-    P: Fn(&E) -> bool,
+    P: Fn(&E) -> D,
+    D: Into<RetryDecision>,
 {
     assert!(
         config.max_attempts >= 1,
@@ -189,10 +217,10 @@ where
     );
 
     let mut last_error: Option<E> = None;
+    let mut delay = Duration::ZERO;
 
     for attempt in 0..config.max_attempts {
         // Wait before retry (except for first attempt)
-        let delay = config.delay_for_attempt(attempt);
         if !delay.is_zero() {
             debug!(
                 "{}: Retry attempt {}/{} after {:?}",
@@ -218,13 +246,24 @@ where
             }
             Err(e) => {
                 // Check if we should retry this error
-                if !should_retry(&e) {
-                    debug!(
-                        "{}: Error is not retryable, failing immediately: {}",
-                        operation_name, e
-                    );
-                    return Err(e);
-                }
+                delay = match should_retry(&e).into() {
+                    RetryDecision::Stop => {
+                        debug!(
+                            "{}: Error is not retryable, failing immediately: {}",
+                            operation_name, e
+                        );
+                        return Err(e);
+                    }
+                    RetryDecision::RetryAfter(hint) if hint > config.max_retry_after => {
+                        warn!(
+                            "{}: Server asked to wait {:?} (limit {:?}), failing immediately: {}",
+                            operation_name, hint, config.max_retry_after, e
+                        );
+                        return Err(e);
+                    }
+                    RetryDecision::RetryAfter(hint) => hint,
+                    RetryDecision::Retry => config.jittered_delay(attempt + 1, delay),
+                };
 
                 let remaining = config.max_attempts - attempt - 1;
                 if remaining > 0 {
@@ -250,6 +289,318 @@ where
     Err(last_error.expect("At least one attempt should have been made"))
 }
 
+// ==================== Circuit Breaker ====================
+
+/// State of a circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through; consecutive failures are counted
+    Closed,
+    /// Calls are rejected without reaching the dependency
+    Open,
+    /// The open period has elapsed; one probe call is let through
+    HalfOpen,
+}
+
+/// Configuration for a circuit breaker
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit (0 disables the breaker)
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a probe is allowed
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// A breaker that never opens
+    pub fn disabled() -> Self {
+        Self {
+            failure_threshold: 0,
+            ..Self::default()
+        }
+    }
+}
+
+/// Returned instead of calling a dependency whose circuit is open
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Circuit breaker for {name} is open (retry in {retry_in:?})")]
+pub struct CircuitOpenError {
+    pub name: String,
+    pub retry_in: Duration,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+    trips: u64,
+    rejected: u64,
+}
+
+/// Closed/open/half-open circuit breaker for one dependency
+///
+/// After `failure_threshold` consecutive failures the circuit opens and calls
+/// fail fast with [`CircuitOpenError`]. Once `open_duration` has passed a
+/// single probe is let through: success closes the circuit, failure reopens it.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: String,
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed circuit breaker
+    pub fn new(name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+            inner: Mutex::new(BreakerInner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probe_in_flight: false,
+                trips: 0,
+                rejected: 0,
+            }),
+        }
+    }
+
+    /// Dependency name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Current state (an open circuit whose open period has elapsed reports half-open)
+    pub fn state(&self) -> CircuitState {
+        let inner = self.lock();
+        match (inner.state, inner.opened_at) {
+            (CircuitState::Open, Some(opened_at))
+                if opened_at.elapsed() >= self.config.open_duration =>
+            {
+                CircuitState::HalfOpen
+            }
+            (state, _) => state,
+        }
+    }
+
+    /// Ask permission to call the dependency
+    pub fn try_acquire(&self) -> Result<(), CircuitOpenError> {
+        self.acquire().map(|_| ())
+    }
+
+    /// Like `try_acquire`, returning whether the call is the half-open probe
+    fn acquire(&self) -> Result<bool, CircuitOpenError> {
+        let mut inner = self.lock();
+        match inner.state {
+            CircuitState::Closed => Ok(false),
+            CircuitState::Open => {
+                let elapsed = inner.opened_at.map(|t| t.elapsed()).unwrap_or_default();
+                if elapsed >= self.config.open_duration {
+                    info!("Circuit breaker for {} is half-open, probing", self.name);
+                    inner.state = CircuitState::HalfOpen;
+                    inner.probe_in_flight = true;
+                    Ok(true)
+                } else {
+                    inner.rejected += 1;
+                    Err(CircuitOpenError {
+                        name: self.name.clone(),
+                        retry_in: self.config.open_duration - elapsed,
+                    })
+                }
+            }
+            CircuitState::HalfOpen => {
+                if inner.probe_in_flight {
+                    inner.rejected += 1;
+                    Err(CircuitOpenError {
+                        name: self.name.clone(),
+                        retry_in: Duration::ZERO,
+                    })
+                } else {
+                    inner.probe_in_flight = true;
+                    Ok(true)
+                }
+            }
+        }
+    }
+
+    /// Record a call that reached a healthy dependency
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        if inner.state != CircuitState::Closed {
+            info!("Circuit breaker for {} closed", self.name);
+        }
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.probe_in_flight = false;
+    }
+
+    /// Record a call that failed because of the dependency
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.consecutive_failures += 1;
+        inner.probe_in_flight = false;
+
+        let trip = match inner.state {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => {
+                self.config.failure_threshold > 0
+                    && inner.consecutive_failures >= self.config.failure_threshold
+            }
+            CircuitState::Open => false,
+        };
+        if trip {
+            warn!(
+                "Circuit breaker for {} opened after {} consecutive failures",
+                self.name, inner.consecutive_failures
+            );
+            inner.state = CircuitState::Open;
+            inner.opened_at = Some(Instant::now());
+            inner.trips += 1;
+        }
+    }
+
+    /// Run `operation` through the breaker
+    ///
+    /// Errors for which `is_failure` returns false (e.g. a 400 for a bad request)
+    /// mean the dependency answered, so they count as successes for the breaker.
+    /// A half-open probe that is dropped before it finishes (e.g. by a timeout)
+    /// counts as a failure, so the probe slot is always released.
+    pub async fn call<T, E, F, Fut, P>(&self, operation: F, is_failure: P) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<CircuitOpenError>,
+        P: Fn(&E) -> bool,
+    {
+        let probe = self.acquire()?;
+        let guard = ProbeGuard {
+            breaker: probe.then_some(self),
+        };
+
+        let result = operation().await;
+        guard.disarm();
+        match &result {
+            Err(e) if is_failure(e) => self.record_failure(),
+            _ => self.record_success(),
+        }
+        result
+    }
+
+    /// Snapshot for the metrics endpoint
+    pub fn report(&self) -> CircuitBreakerReport {
+        let state = self.state();
+        let inner = self.lock();
+        CircuitBreakerReport {
+            name: self.name.clone(),
+            state,
+            consecutive_failures: inner.consecutive_failures,
+            trips: inner.trips,
+            rejected: inner.rejected,
+        }
+    }
+}
+
+/// Holds the half-open probe slot while `CircuitBreaker::call` awaits the probe
+struct ProbeGuard<'a> {
+    breaker: Option<&'a CircuitBreaker>,
+}
+
+impl ProbeGuard<'_> {
+    /// The probe finished; the caller records its outcome
+    fn disarm(mut self) {
+        self.breaker = None;
+    }
+}
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(breaker) = self.breaker.take() {
+            warn!(
+                "Circuit breaker probe for {} was cancelled, counting it as a failure",
+                breaker.name
+            );
+            breaker.record_failure();
+        }
+    }
+}
+
+/// Circuit breaker state for the metrics endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitBreakerReport {
+    /// Dependency name
+    pub name: String,
+
+    /// Current state
+    pub state: CircuitState,
+
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+
+    /// Number of times the circuit has opened
+    pub trips: u64,
+
+    /// Calls rejected while open
+    pub rejected: u64,
+}
+
+/// Global registry of per-dependency circuit breakers
+pub struct CircuitBreakers {
+    breakers: Mutex<HashMap<String, Arc<CircuitBreaker>>>,
+}
+
+/// Global registry instance (initialized lazily)
+static CIRCUIT_BREAKERS: OnceLock<CircuitBreakers> = OnceLock::new();
+
+impl CircuitBreakers {
+    /// Get the global registry
+    pub fn global() -> &'static CircuitBreakers {
+        CIRCUIT_BREAKERS.get_or_init(|| CircuitBreakers {
+            breakers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Get the breaker for a dependency, creating it with `config` on first use
+    pub fn get(&self, name: &str, config: &CircuitBreakerConfig) -> Arc<CircuitBreaker> {
+        let mut breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        breakers
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(CircuitBreaker::new(name, config.clone())))
+            .clone()
+    }
+
+    /// State of every breaker, sorted by name
+    pub fn report(&self) -> Vec<CircuitBreakerReport> {
+        let breakers: Vec<Arc<CircuitBreaker>> = self
+            .breakers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        let mut reports: Vec<_> = breakers.iter().map(|b| b.report()).collect();
+        reports.sort_by(|a, b| a.name.cmp(&b.name));
+        reports
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -948,4 +1299,320 @@ mod tests {
         assert_eq!(result.unwrap(), 42);
         assert_eq!(counter.load(Ordering::SeqCst), 8); // Succeeded on 8th attempt
     }
+
+    // ==================== Jitter Tests ====================
+
+    #[test]
+    fn test_jitter_none_is_deterministic() {
+        let config = RetryConfig::new(4, Duration::from_millis(100));
+
+        assert_eq!(config.jitter, Jitter::None);
+        assert_eq!(
+            config.jittered_delay(2, Duration::from_millis(100)),
+            Duration::from_millis(200)
+        );
+    }
+
+    #[test]
+    fn test_full_jitter_stays_within_backoff() {
+        let config = RetryConfig::new(4, Duration::from_millis(100)).with_jitter(Jitter::Full);
+
+        for _ in 0..200 {
+            let delay = config.jittered_delay(3, Duration::ZERO);
+            assert!(delay <= Duration::from_millis(400), "got {:?}", delay);
+        }
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_bounds() {
+        let config = RetryConfig::new(10, Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(500))
+            .with_jitter(Jitter::Decorrelated);
+
+        // First retry has no previous delay: exactly the initial delay
+        assert_eq!(
+            config.jittered_delay(1, Duration::ZERO),
+            Duration::from_millis(100)
+        );
+
+        for _ in 0..200 {
+            let delay = config.jittered_delay(2, Duration::from_millis(120));
+            assert!(delay >= Duration::from_millis(100), "got {:?}", delay);
+            assert!(delay <= Duration::from_millis(360), "got {:?}", delay);
+
+            let capped = config.jittered_delay(5, Duration::from_millis(400));
+            assert!(capped <= Duration::from_millis(500), "got {:?}", capped);
+        }
+    }
+
+    #[test]
+    fn test_api_call_preset_uses_decorrelated_jitter() {
+        assert_eq!(RetryConfig::api_call().jitter, Jitter::Decorrelated);
+        assert_eq!(RetryConfig::health_check().jitter, Jitter::None);
+    }
+
+    // ==================== Delay Hint Tests ====================
+
+    #[test]
+    fn test_retry_decision_from_bool() {
+        assert_eq!(RetryDecision::from(true), RetryDecision::Retry);
+        assert_eq!(RetryDecision::from(false), RetryDecision::Stop);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_if_honors_delay_hint() {
+        // The backoff would be 10s; the hint replaces it
+        let config = RetryConfig::new(2, Duration::from_secs(10));
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let start = std::time::Instant::now();
+        let result: Result<i32, &str> = with_retry_if(
+            &config,
+            "hinted",
+            || {
+                let c = counter_clone.clone();
+                async move {
+                    if c.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err("rate limited")
+                    } else {
+                        Ok(7)
+                    }
+                }
+            },
+            |_| RetryDecision::RetryAfter(Duration::from_millis(50)),
+        )
+        .await;
+        let elapsed = start.elapsed();
+
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        assert!(elapsed >= Duration::from_millis(50), "got {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(2), "got {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_if_gives_up_on_excessive_hint() {
+        let config = RetryConfig::new(3, Duration::from_millis(1))
+            .with_max_retry_after(Duration::from_secs(5));
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let start = std::time::Instant::now();
+        let result: Result<i32, &str> = with_retry_if(
+            &config,
+            "long_hint",
+            || {
+                let c = counter_clone.clone();
+                async move {
+                    c.fetch_add(1, Ordering::SeqCst);
+                    Err("come back in an hour")
+                }
+            },
+            |_| RetryDecision::RetryAfter(Duration::from_secs(3600)),
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err(), "come back in an hour");
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    // ==================== Circuit Breaker Tests ====================
+
+    fn test_breaker(threshold: u32, open_ms: u64) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "test",
+            CircuitBreakerConfig {
+                failure_threshold: threshold,
+                open_duration: Duration::from_millis(open_ms),
+            },
+        )
+    }
+
+    #[test]
+    fn test_circuit_opens_after_threshold() {
+        let breaker = test_breaker(3, 60_000);
+
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_ok());
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let err = breaker.try_acquire().unwrap_err();
+        assert_eq!(err.name, "test");
+        assert!(err.retry_in > Duration::ZERO);
+
+        let report = breaker.report();
+        assert_eq!(report.trips, 1);
+        assert_eq!(report.rejected, 1);
+        assert_eq!(report.consecutive_failures, 3);
+    }
+
+    #[test]
+    fn test_circuit_success_resets_failure_count() {
+        let breaker = test_breaker(2, 60_000);
+
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_half_open_allows_single_probe() {
+        let breaker = test_breaker(1, 10);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        assert!(breaker.try_acquire().is_ok(), "probe should be let through");
+        assert!(
+            breaker.try_acquire().is_err(),
+            "second caller waits for probe"
+        );
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn test_disabled_circuit_never_opens() {
+        let breaker = CircuitBreaker::new("disabled", CircuitBreakerConfig::disabled());
+
+        for _ in 0..100 {
+            breaker.record_failure();
+        }
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn test_circuit_failed_probe_reopens() {
+        let breaker = test_breaker(1, 10);
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert!(breaker.try_acquire().is_ok());
+        breaker.record_failure();
+
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.report().trips, 2);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_cancelled_probe_releases_slot() {
+        let breaker = test_breaker(1, 10);
+        breaker.record_failure();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // The probe is cancelled by a timeout before it completes
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            breaker.call(
+                || async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok::<_, anyhow::Error>(())
+                },
+                |_| true,
+            ),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.report().trips, 2);
+
+        // After the open period the next call probes and closes the circuit
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let result: anyhow::Result<()> = breaker.call(|| async { Ok(()) }, |_| true).await;
+        assert!(result.is_ok());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_call_short_circuits_when_open() {
+        let breaker = test_breaker(2, 60_000);
+        let counter = Arc::new(AtomicU32::new(0));
+
+        for _ in 0..3 {
+            let c = counter.clone();
+            let result: anyhow::Result<()> = breaker
+                .call(
+                    || async move {
+                        c.fetch_add(1, Ordering::SeqCst);
+                        Err(anyhow::anyhow!("503 unavailable"))
+                    },
+                    |_| true,
+                )
+                .await;
+            assert!(result.is_err());
+        }
+
+        // The third call never reached the operation
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_call_ignores_non_failures() {
+        let breaker = test_breaker(1, 60_000);
+
+        let result: anyhow::Result<()> = breaker
+            .call(
+                || async { Err(anyhow::anyhow!("400 bad request")) },
+                |_| false,
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_open_error_downcasts() {
+        let breaker = test_breaker(1, 60_000);
+        breaker.record_failure();
+
+        let error = breaker
+            .call(|| async { Ok::<(), anyhow::Error>(()) }, |_| true)
+            .await
+            .unwrap_err();
+
+        assert!(error.downcast_ref::<CircuitOpenError>().is_some());
+    }
+
+    #[test]
+    fn test_circuit_breakers_registry() {
+        let registry = CircuitBreakers::global();
+        let config = CircuitBreakerConfig::default();
+        let a = registry.get("registry-test-b", &config);
+        let b = registry.get("registry-test-b", &config);
+        registry.get("registry-test-a", &config);
+
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let names: Vec<String> = registry
+            .report()
+            .into_iter()
+            .map(|r| r.name)
+            .filter(|n| n.starts_with("registry-test-"))
+            .collect();
+        assert_eq!(names, vec!["registry-test-a", "registry-test-b"]);
+    }
+
+    #[test]
+    fn test_circuit_state_serializes_snake_case() {
+        assert_eq!(
+            serde_json::to_string(&CircuitState::HalfOpen).unwrap(),
+            "\"half_open\""
+        );
+    }
 }
@@ -1,32 +1,65 @@
 use crate::config::Config;
-use crate::db::Database;
-use crate::openai;
+use crate::db::{Database, NewStoryItem};
+use crate::digest::{PreviousCoverage, RenderFormat};
+use crate::embeddings;
+use crate::openai::{self, GeneratedSummary};
 use crate::rss;
-use crate::telegram;
-use anyhow::Result;
+use crate::stories;
+use crate::telegram::{self, TelegramClient};
+use anyhow::{Context, Result};
 use chrono::{NaiveTime, TimeZone, Timelike};
 use std::sync::Arc;
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::{error, info};
-
-/// Initialize and start the scheduler
-pub async fn start_scheduler(config: Arc<Config>, db: Arc<Database>) -> Result<JobScheduler> {
+use tracing::{error, info, warn};
+
+/// Initialize and start the scheduler with jobs for every digest
+pub async fn start_scheduler(
+    config: Arc<Config>,
+    db: Arc<Database>,
+    telegram: Arc<TelegramClient>,
+) -> Result<JobScheduler> {
     let scheduler = JobScheduler::new().await?;
 
+    for topic in &config.topics {
+        let topic_config = Arc::new(config.for_topic(topic));
+        schedule_digest(
+            &scheduler,
+            topic_config,
+            Arc::clone(&db),
+            Arc::clone(&telegram),
+        )
+        .await?;
+    }
+
+    scheduler.start().await?;
+    info!("✓ Scheduler started");
+
+    Ok(scheduler)
+}
+
+/// Add the jobs of one digest (`config` is scoped with `Config::for_topic`)
+async fn schedule_digest(
+    scheduler: &JobScheduler,
+    config: Arc<Config>,
+    db: Arc<Database>,
+    telegram: Arc<TelegramClient>,
+) -> Result<()> {
+    let digest_id = config.topic.id.clone();
+
     // Read usernames from file once at startup
-    let usernames_content = std::fs::read_to_string(&config.usernames_file)?;
-    let usernames: Vec<String> = usernames_content
-        .lines()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+    let usernames = read_usernames(&config.usernames_file)?;
 
-    info!("Loaded {} usernames for scheduled fetches", usernames.len());
+    info!(
+        "[{}] Loaded {} usernames for scheduled fetches",
+        digest_id,
+        usernames.len()
+    );
 
     // Calculate processing time offset based on user count
     let offset_seconds = estimate_processing_seconds(usernames.len());
     info!(
-        "Estimated processing time: {}s (~{} min) - jobs will start early to ensure on-time delivery",
+        "[{}] Estimated processing time: {}s (~{} min) - jobs will start early to ensure on-time delivery",
+        digest_id,
         offset_seconds,
         offset_seconds / 60
     );
@@ -35,30 +68,35 @@ pub async fn start_scheduler(config: Arc<Config>, db: Arc<Database>) -> Result<J
     for time in &config.schedule_times {
         let cron_expr = time_to_cron(time, offset_seconds)?;
         info!(
-            "Scheduling job for {} Peru time (cron: {}, starts ~{}s early)",
-            time, cron_expr, offset_seconds
+            "[{}] Scheduling job for {} Peru time (cron: {}, starts ~{}s early)",
+            digest_id, time, cron_expr, offset_seconds
         );
 
         let config_clone = Arc::clone(&config);
         let db_clone = Arc::clone(&db);
+        let telegram_clone = Arc::clone(&telegram);
         let usernames_clone = usernames.clone();
         let target_time = time.clone();
 
         let job = Job::new_async(cron_expr.as_str(), move |_uuid, _l| {
             let config = Arc::clone(&config_clone);
             let db = Arc::clone(&db_clone);
+            let telegram = Arc::clone(&telegram_clone);
             let usernames = usernames_clone.clone();
             let target = target_time.clone();
 
             Box::pin(async move {
                 info!(
-                    "⏰ Scheduled job triggered (target send time: {} Peru)",
-                    target
+                    "⏰ Scheduled job for digest '{}' triggered (target send time: {} Peru)",
+                    config.topic.id, target
                 );
-                if let Err(e) = run_summary_job(&config, &db, &usernames, Some(&target)).await {
+                if let Err(e) =
+                    run_summary_job(&config, &db, &telegram, &usernames, Some(&target)).await
+                {
                     error!("Scheduled job failed: {:?}", e);
                     // Notify admin of job failure
-                    telegram::notify_admin_error(&config, "Scheduled summary job", &e).await;
+                    let context = format!("Scheduled summary job ({})", config.topic.id);
+                    telegram::notify_admin_error(&config, &telegram, &context, &e).await;
                 }
             })
         })?;
@@ -66,10 +104,18 @@ pub async fn start_scheduler(config: Arc<Config>, db: Arc<Database>) -> Result<J
         scheduler.add(job).await?;
     }
 
-    scheduler.start().await?;
-    info!("✓ Scheduler started");
+    Ok(())
+}
 
-    Ok(scheduler)
+/// Read a usernames file (one username per line, blank lines ignored)
+fn read_usernames(path: &str) -> Result<Vec<String>> {
+    let usernames_content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read usernames file {}", path))?;
+    Ok(usernames_content
+        .lines()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect())
 }
 
 /// Calculate estimated processing time in seconds based on user count
@@ -117,10 +163,11 @@ fn time_to_cron(time: &str, offset_seconds: u32) -> Result<String> {
 async fn run_summary_job(
     config: &Config,
     db: &Database,
+    telegram: &TelegramClient,
     usernames: &[String],
     target_send_time: Option<&str>,
 ) -> Result<()> {
-    info!("Starting summary job");
+    info!("Starting summary job for digest '{}'", config.topic.id);
 
     // Fetch tweets
     info!("Fetching tweets from RSS feeds");
@@ -136,11 +183,26 @@ async fn run_summary_job(
     // Generate summary
     info!("Generating summary with OpenAI");
     let client = reqwest::Client::new();
-    let summary = openai::summarize_tweets(&client, config, &tweets).await?;
-
-    // Save summary to database and get the ID for translation caching
-    let summary_id = db.save_summary(&summary).await?;
+    let previous = load_previous_coverage(config, db).await;
+    let tweets = embeddings::prepare_tweets(&client, config, db, tweets, &previous).await;
+    let mut generated = openai::generate_summary(&client, config, &tweets, &previous).await?;
+    let story_items = mark_stories(config, db, &mut generated).await;
+    let summary = generated.content;
+
+    // Save summary (and its structured digest) and get the ID for translation caching
+    let summary_id = db
+        .save_generated_summary(
+            &config.topic.id,
+            &summary,
+            generated.digest.as_ref(),
+            Some(&generated.lint),
+            Some(&config.prompts.summary_version()),
+            Some(&generated.model),
+        )
+        .await?;
     info!("✓ Summary saved to database (id: {})", summary_id);
+    record_stories(config, db, summary_id, &story_items).await;
+    telegram::notify_admin_fallback(config, telegram, &generated.model).await;
 
     // If we have a target send time, wait until that time before sending
     if let Some(target_time_str) = target_send_time {
@@ -155,13 +217,77 @@ async fn run_summary_job(
 
     // Send to all subscribers (with language-specific translations)
     info!("Sending summary via Telegram");
-    telegram::send_to_subscribers(config, db, &summary, summary_id).await?;
+    telegram::send_to_subscribers(config, db, telegram, &summary, summary_id).await?;
 
     info!("✓ Summary job completed successfully");
 
     Ok(())
 }
 
+/// Summaries loaded for continuity (all a digest keeps, see `save_generated_summary`)
+const CONTINUITY_HISTORY: i64 = 10;
+
+/// Stories of the digest's recent summaries, so the next one doesn't repeat them.
+/// Empty when continuity is disabled or the summaries can't be read.
+async fn load_previous_coverage(config: &Config, db: &Database) -> PreviousCoverage {
+    if config.summary_continuity_digests == 0 {
+        return PreviousCoverage::default();
+    }
+
+    match db
+        .get_recent_digest_summaries(&config.topic.id, CONTINUITY_HISTORY)
+        .await
+    {
+        Ok(summaries) => PreviousCoverage::from_summaries(&summaries),
+        Err(e) => {
+            warn!(
+                "Failed to load previous summaries, generating without continuity: {:#}",
+                e
+            );
+            PreviousCoverage::default()
+        }
+    }
+}
+
+/// Match the digest's bullets to developing stories and add their "Day N"
+/// markers to the summary. Returns the story items to record once the summary
+/// is saved; empty for free-form summaries or if stories can't be read.
+async fn mark_stories(
+    config: &Config,
+    db: &Database,
+    generated: &mut GeneratedSummary,
+) -> Vec<NewStoryItem> {
+    let Some(digest) = generated.digest.as_mut() else {
+        return Vec::new();
+    };
+
+    match stories::track(db, &config.topic.id, digest, chrono::Utc::now()).await {
+        Ok(items) => {
+            if items.iter().any(|item| item.story_id.is_some()) {
+                generated.content = digest.render(RenderFormat::Markdown);
+            }
+            items
+        }
+        Err(e) => {
+            warn!("Failed to match developing stories: {:#}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Record the summary's story items (a failure only loses story tracking)
+async fn record_stories(config: &Config, db: &Database, summary_id: i64, items: &[NewStoryItem]) {
+    if items.is_empty() {
+        return;
+    }
+    if let Err(e) = db
+        .save_story_items(&config.topic.id, summary_id, items)
+        .await
+    {
+        warn!("Failed to record developing stories: {:#}", e);
+    }
+}
+
 /// Calculate how long to wait until target time.
 /// Returns Some(duration) if we need to wait, None if time has already passed.
 /// This pure function is easily testable with any fixed `now_utc` value.
@@ -238,30 +364,47 @@ async fn wait_until_target_time(target_time_str: &str) -> Result<()> {
     Ok(())
 }
 
-/// Manually trigger a summary job (for /trigger endpoint)
+/// Manually trigger a summary job for the config's digest (for /trigger endpoint)
 /// Sends immediately without waiting for a target time
-pub async fn trigger_summary(config: &Config, db: &Database) -> Result<()> {
-    // Read usernames from file
-    let usernames_content = std::fs::read_to_string(&config.usernames_file)?;
-    let usernames: Vec<String> = usernames_content
-        .lines()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+pub async fn trigger_summary(
+    config: &Config,
+    db: &Database,
+    telegram: &TelegramClient,
+) -> Result<()> {
+    let usernames = read_usernames(&config.usernames_file)?;
+
+    run_summary_job(config, db, telegram, &usernames, None).await
+}
+
+/// Manually trigger the summary jobs of every digest, one after another.
+/// A failing digest does not stop the others; the first error is returned.
+pub async fn trigger_all_summaries(
+    config: &Config,
+    db: &Database,
+    telegram: &TelegramClient,
+) -> Result<()> {
+    let mut first_error = None;
+    for topic in &config.topics {
+        if let Err(e) = trigger_summary(&config.for_topic(topic), db, telegram).await {
+            error!("Summary job for digest '{}' failed: {:?}", topic.id, e);
+            first_error.get_or_insert_with(|| e.context(format!("Digest '{}' failed", topic.id)));
+        }
+    }
 
-    run_summary_job(config, db, &usernames, None).await
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
 }
 
 /// Generate a fresh summary and save to database WITHOUT broadcasting to subscribers.
 /// Use this for test endpoints where you want to generate content but only send to a specific user.
-pub async fn generate_summary_only(config: &Config, db: &Database) -> Result<String> {
-    // Read usernames from file
-    let usernames_content = std::fs::read_to_string(&config.usernames_file)?;
-    let usernames: Vec<String> = usernames_content
-        .lines()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+pub async fn generate_summary_only(
+    config: &Config,
+    db: &Database,
+    telegram: &TelegramClient,
+) -> Result<String> {
+    let usernames = read_usernames(&config.usernames_file)?;
 
     info!("Starting summary generation (no broadcast)");
 
@@ -278,11 +421,26 @@ pub async fn generate_summary_only(config: &Config, db: &Database) -> Result<Str
     // Generate summary
     info!("Generating summary with OpenAI");
     let client = reqwest::Client::new();
-    let summary = openai::summarize_tweets(&client, config, &tweets).await?;
+    let previous = load_previous_coverage(config, db).await;
+    let tweets = embeddings::prepare_tweets(&client, config, db, tweets, &previous).await;
+    let mut generated = openai::generate_summary(&client, config, &tweets, &previous).await?;
+    let story_items = mark_stories(config, db, &mut generated).await;
+    let summary = generated.content;
 
     // Save summary to database
-    db.save_summary(&summary).await?;
+    let summary_id = db
+        .save_generated_summary(
+            &config.topic.id,
+            &summary,
+            generated.digest.as_ref(),
+            Some(&generated.lint),
+            Some(&config.prompts.summary_version()),
+            Some(&generated.model),
+        )
+        .await?;
+    record_stories(config, db, summary_id, &story_items).await;
     info!("✓ Summary generated and saved (not broadcast)");
+    telegram::notify_admin_fallback(config, telegram, &generated.model).await;
 
     Ok(summary)
 }
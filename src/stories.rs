@@ -0,0 +1,339 @@
+//! Developing stories followed across digests.
+//!
+//! Big stories (a launch, then benchmarks, reactions and a postmortem) show up
+//! in many digests. After a structured digest is generated, each bullet is
+//! matched to the digest's open stories: a shared URL is a match, and so are
+//! enough shared entities (product, company and account names). Matched bullets
+//! get a [`StoryMarker`], rendered as "🧵 Day 3 · /story_12", and every bullet
+//! is recorded as a story item once the summary is saved, starting a new story
+//! when nothing matched. `/story <id>` shows a story's timeline.
+
+use crate::db::{Database, NewStoryItem, Story, StoryItem};
+use crate::digest::{Digest, StoryMarker};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+
+/// Stories not seen for this many days are not continued
+pub const STORY_WINDOW_DAYS: i64 = 7;
+
+/// Shared entities needed to match a bullet to a story without a shared URL
+const MIN_SHARED_ENTITIES: usize = 2;
+
+/// Capitalized words that say nothing about which story a bullet belongs to
+const GENERIC_WORDS: [&str; 20] = [
+    "ai", "an", "and", "api", "day", "for", "how", "in", "its", "llm", "llms", "ml", "new", "of",
+    "on", "our", "the", "this", "update", "why",
+];
+
+/// Normalized entity names in `text`: capitalized words of two or more
+/// characters (e.g. "OpenAI", "GPT-5", "Llama"), and @handles without the "@".
+/// Sorted and deduplicated.
+pub fn extract_entities(text: &str) -> Vec<String> {
+    let mut entities: Vec<String> = text
+        .split_whitespace()
+        .filter_map(|token| {
+            let token = token.trim_matches(|c: char| !c.is_alphanumeric() && c != '@');
+            let token = token
+                .strip_suffix("'s")
+                .or_else(|| token.strip_suffix("’s"))
+                .unwrap_or(token);
+            let (handle, word) = match token.strip_prefix('@') {
+                Some(handle) => (true, handle),
+                None => (false, token),
+            };
+            // Skip initials and amounts ("$50M", "2x")
+            if word.chars().count() < 2 || !word.starts_with(|c: char| c.is_alphabetic()) {
+                return None;
+            }
+            if !handle && !word.chars().any(|c| c.is_uppercase()) {
+                return None;
+            }
+            let word = word.to_lowercase();
+            (!GENERIC_WORDS.contains(&word.as_str())).then_some(word)
+        })
+        .collect();
+    entities.sort();
+    entities.dedup();
+    entities
+}
+
+fn normalize_url(url: &str) -> &str {
+    url.trim_end_matches('/')
+}
+
+/// An open story with the URLs and entities of all its items
+#[derive(Debug, Clone)]
+pub struct ActiveStory {
+    pub story: Story,
+    urls: HashSet<String>,
+    entities: HashSet<String>,
+}
+
+impl ActiveStory {
+    /// Group items under their stories (stories keep their order)
+    pub fn from_items(stories: Vec<Story>, items: &[StoryItem]) -> Vec<ActiveStory> {
+        stories
+            .into_iter()
+            .map(|story| {
+                let items = items.iter().filter(|i| i.story_id == story.id);
+                ActiveStory {
+                    urls: items
+                        .clone()
+                        .map(|i| normalize_url(&i.url).to_string())
+                        .collect(),
+                    entities: items.flat_map(|i| i.entities.iter().cloned()).collect(),
+                    story,
+                }
+            })
+            .collect()
+    }
+
+    /// How strongly a bullet matches: Some((shares URL, shared entities)), or
+    /// None if it doesn't match
+    fn match_score(&self, url: &str, entities: &[String]) -> Option<(bool, usize)> {
+        let same_url = self.urls.contains(normalize_url(url));
+        let shared = entities
+            .iter()
+            .filter(|e| self.entities.contains(*e))
+            .count();
+        // Most of the bullet's entities must be shared, so one common name
+        // (e.g. the company) doesn't merge unrelated stories
+        let entity_match = shared >= MIN_SHARED_ENTITIES && shared * 2 >= entities.len();
+        (same_url || entity_match).then_some((same_url, shared))
+    }
+}
+
+/// Day of a story first seen at `first_seen`, counting from 1
+pub fn story_day(first_seen: DateTime<Utc>, now: DateTime<Utc>) -> u32 {
+    ((now - first_seen).num_hours().max(0) / 24 + 1) as u32
+}
+
+/// Match every bullet of `digest` to the best active story, set the markers of
+/// matched bullets, and return one item per bullet (in digest order) to record
+/// with `Database::save_story_items`.
+pub fn assign(
+    digest: &mut Digest,
+    active: &[ActiveStory],
+    now: DateTime<Utc>,
+) -> Vec<NewStoryItem> {
+    let mut items = Vec::new();
+    for bullet in digest
+        .sections
+        .iter_mut()
+        .flat_map(|s| s.bullets.iter_mut())
+    {
+        let entities = extract_entities(&format!("{} {}", bullet.title, bullet.link_label));
+
+        // Ties go to the most recently seen story (`active` is ordered that way)
+        let mut best: Option<(&ActiveStory, (bool, usize))> = None;
+        for story in active {
+            if let Some(score) = story.match_score(&bullet.url, &entities) {
+                if best.is_none_or(|(_, best_score)| score > best_score) {
+                    best = Some((story, score));
+                }
+            }
+        }
+
+        bullet.story = best.map(|(active, _)| StoryMarker {
+            id: active.story.id,
+            day: story_day(active.story.first_seen_at, now),
+        });
+        items.push(NewStoryItem {
+            story_id: bullet.story.map(|m| m.id),
+            headline: bullet.title.clone(),
+            url: bullet.url.clone(),
+            entities,
+        });
+    }
+    items
+}
+
+/// Load the digest's open stories and [`assign`] the digest's bullets to them
+pub async fn track(
+    db: &Database,
+    digest_id: &str,
+    digest: &mut Digest,
+    now: DateTime<Utc>,
+) -> Result<Vec<NewStoryItem>> {
+    let stories = db
+        .get_active_stories(digest_id, now - Duration::days(STORY_WINDOW_DAYS))
+        .await?;
+    let ids: Vec<i64> = stories.iter().map(|s| s.id).collect();
+    let items = db.get_story_items(&ids).await?;
+    Ok(assign(
+        digest,
+        &ActiveStory::from_items(stories, &items),
+        now,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::{DigestBullet, DigestSection};
+
+    fn bullet(title: &str, label: &str, url: &str) -> DigestBullet {
+        DigestBullet {
+            title: title.to_string(),
+            significance: "why it matters".to_string(),
+            link_label: label.to_string(),
+            url: url.to_string(),
+            source_tweet_ids: vec![],
+            story: None,
+        }
+    }
+
+    fn digest(bullets: Vec<DigestBullet>) -> Digest {
+        Digest {
+            sections: vec![DigestSection {
+                title: "🚀 Releases".to_string(),
+                bullets,
+            }],
+        }
+    }
+
+    fn active_story(id: i64, hours_ago: i64, items: &[(&str, &str)]) -> ActiveStory {
+        let now = Utc::now();
+        let story = Story {
+            id,
+            digest_id: "default".to_string(),
+            title: items[0].0.to_string(),
+            first_seen_at: now - Duration::hours(hours_ago),
+            last_seen_at: now,
+        };
+        let items: Vec<StoryItem> = items
+            .iter()
+            .enumerate()
+            .map(|(i, (headline, url))| StoryItem {
+                id: i as i64,
+                story_id: id,
+                summary_id: 1,
+                headline: headline.to_string(),
+                url: url.to_string(),
+                entities: extract_entities(headline),
+                created_at: now,
+            })
+            .collect();
+        ActiveStory::from_items(vec![story], &items).remove(0)
+    }
+
+    // ==================== Entity Extraction Tests ====================
+
+    #[test]
+    fn test_extract_entities_keeps_names_and_handles() {
+        assert_eq!(
+            extract_entities("OpenAI's GPT-5 beats Claude on SWE-bench, says @karpathy"),
+            vec!["claude", "gpt-5", "karpathy", "openai", "swe-bench"]
+        );
+    }
+
+    #[test]
+    fn test_extract_entities_skips_generic_words_and_numbers() {
+        assert_eq!(
+            extract_entities("Update: The new AI model raised $50M in 2025"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            extract_entities("Acme shipped Widget 2 (Acme Widget 2 launch)"),
+            vec!["acme", "widget"]
+        );
+    }
+
+    // ==================== Assignment Tests ====================
+
+    #[test]
+    fn test_assign_matches_shared_url() {
+        let active = [active_story(
+            3,
+            30,
+            &[("Something else entirely", "https://x.com/acme/status/1/")],
+        )];
+        let mut digest = digest(vec![bullet(
+            "Benchmarks are in",
+            "independent benchmark thread",
+            "https://x.com/acme/status/1",
+        )]);
+
+        let items = assign(&mut digest, &active, Utc::now());
+
+        assert_eq!(
+            digest.sections[0].bullets[0].story,
+            Some(StoryMarker { id: 3, day: 2 })
+        );
+        assert_eq!(items[0].story_id, Some(3));
+    }
+
+    #[test]
+    fn test_assign_matches_shared_entities() {
+        let active = [active_story(
+            5,
+            2,
+            &[("Acme shipped Widget 2", "https://x.com/acme/status/1")],
+        )];
+        let mut digest = digest(vec![
+            bullet(
+                "Widget 2 benchmarks from Acme",
+                "LMArena Widget results",
+                "https://x.com/lmarena/status/9",
+            ),
+            bullet(
+                "Acme raised $50M",
+                "Acme Series B post",
+                "https://x.com/acme/status/10",
+            ),
+        ]);
+
+        let items = assign(&mut digest, &active, Utc::now());
+
+        assert_eq!(
+            digest.sections[0].bullets[0].story,
+            Some(StoryMarker { id: 5, day: 1 })
+        );
+        // Only the company name is shared: a different story
+        assert_eq!(digest.sections[0].bullets[1].story, None);
+        assert_eq!(items[1].story_id, None);
+        assert_eq!(items[1].entities, vec!["acme", "series"]);
+    }
+
+    #[test]
+    fn test_assign_prefers_url_match_then_most_shared_entities() {
+        let active = [
+            active_story(
+                1,
+                10,
+                &[("Foo Labs Bar Baz paper", "https://x.com/a/status/1")],
+            ),
+            active_story(2, 10, &[("Unrelated", "https://x.com/b/status/2")]),
+            active_story(
+                3,
+                10,
+                &[("Foo Labs Bar release", "https://x.com/c/status/3")],
+            ),
+        ];
+        let mut by_url = digest(vec![bullet(
+            "Foo Labs Bar Baz",
+            "Foo Labs post",
+            "https://x.com/b/status/2",
+        )]);
+        assign(&mut by_url, &active, Utc::now());
+        assert_eq!(by_url.sections[0].bullets[0].story.unwrap().id, 2);
+
+        let mut by_entities = digest(vec![bullet(
+            "Foo Labs Bar Baz",
+            "Foo Labs post",
+            "https://x.com/d/status/4",
+        )]);
+        assign(&mut by_entities, &active, Utc::now());
+        assert_eq!(by_entities.sections[0].bullets[0].story.unwrap().id, 1);
+    }
+
+    #[test]
+    fn test_story_day_counts_from_one() {
+        let now = Utc::now();
+        assert_eq!(story_day(now, now), 1);
+        assert_eq!(story_day(now - Duration::hours(23), now), 1);
+        assert_eq!(story_day(now - Duration::hours(48), now), 3);
+        assert_eq!(story_day(now + Duration::hours(1), now), 1);
+    }
+}
@@ -0,0 +1,223 @@
+//! Inline keyboards and callback data.
+//!
+//! Buttons carry namespaced callback data, `<namespace>:<value>` (e.g.
+//! `lang:es`), within the 64 bytes the Bot API allows. A pressed button's
+//! data is parsed back into a [`CallbackAction`]; anything else (buttons from
+//! an older version, data forged by a modified client) is rejected, so a
+//! button can only run the action its namespace names, for the chat that
+//! pressed it.
+
+use crate::i18n::Language;
+use serde::{Deserialize, Serialize};
+
+/// Maximum size of a button's callback data, in bytes
+pub const CALLBACK_DATA_LIMIT: usize = 64;
+
+/// Inline keyboard attached to a message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InlineKeyboardMarkup {
+    pub inline_keyboard: Vec<Vec<InlineKeyboardButton>>,
+}
+
+/// One inline keyboard button
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InlineKeyboardButton {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_data: Option<String>,
+}
+
+impl InlineKeyboardMarkup {
+    /// Keyboard with one row per entry of `rows`
+    pub fn new(rows: Vec<Vec<InlineKeyboardButton>>) -> Self {
+        Self {
+            inline_keyboard: rows,
+        }
+    }
+}
+
+impl InlineKeyboardButton {
+    /// Button that sends `action` back as a callback query
+    pub fn callback(text: &str, action: &CallbackAction) -> Self {
+        Self {
+            text: text.to_string(),
+            callback_data: Some(action.encode()),
+        }
+    }
+}
+
+/// What a button does when pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackAction {
+    /// Set the subscriber's language (`lang:<code>`)
+    Language(Language),
+    /// Subscribe to the main digest (`digest:subscribe`)
+    Subscribe,
+    /// Unsubscribe from all digests (`digest:unsubscribe`)
+    Unsubscribe,
+    /// Rate a delivered digest from 1 to 5 (`rate:<summary_id>:<rating>`)
+    Rate { summary_id: i64, rating: u8 },
+}
+
+impl CallbackAction {
+    /// Callback data for the action
+    pub fn encode(&self) -> String {
+        let data = match self {
+            Self::Language(language) => format!("lang:{}", language.code()),
+            Self::Subscribe => "digest:subscribe".to_string(),
+            Self::Unsubscribe => "digest:unsubscribe".to_string(),
+            Self::Rate { summary_id, rating } => format!("rate:{}:{}", summary_id, rating),
+        };
+        debug_assert!(data.len() <= CALLBACK_DATA_LIMIT);
+        data
+    }
+
+    /// Parse callback data; `None` for unknown namespaces or values
+    pub fn parse(data: &str) -> Option<Self> {
+        if data.len() > CALLBACK_DATA_LIMIT {
+            return None;
+        }
+        match data.split_once(':')? {
+            ("lang", code) => Language::from_code(code).ok().map(Self::Language),
+            ("digest", "subscribe") => Some(Self::Subscribe),
+            ("digest", "unsubscribe") => Some(Self::Unsubscribe),
+            ("rate", value) => {
+                let (summary_id, rating) = value.split_once(':')?;
+                let summary_id = summary_id.parse().ok().filter(|id| *id > 0)?;
+                let rating = rating.parse().ok().filter(|r| (1..=5).contains(r))?;
+                Some(Self::Rate { summary_id, rating })
+            }
+            _ => None,
+        }
+    }
+
+    /// The bot command the action runs, so a button behaves exactly like
+    /// typing the command (`None` for actions with no command)
+    pub fn command(&self) -> Option<String> {
+        match self {
+            Self::Language(language) => Some(format!("/language {}", language.code())),
+            Self::Subscribe => Some("/subscribe".to_string()),
+            Self::Unsubscribe => Some("/unsubscribe".to_string()),
+            Self::Rate { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Callback Data Tests ====================
+
+    #[test]
+    fn test_callback_actions_round_trip() {
+        for action in [
+            CallbackAction::Language(Language::ENGLISH),
+            CallbackAction::Language(Language::SPANISH),
+            CallbackAction::Subscribe,
+            CallbackAction::Unsubscribe,
+            CallbackAction::Rate {
+                summary_id: i64::MAX,
+                rating: 5,
+            },
+        ] {
+            let data = action.encode();
+            assert!(data.len() <= CALLBACK_DATA_LIMIT);
+            assert_eq!(CallbackAction::parse(&data), Some(action));
+        }
+    }
+
+    #[test]
+    fn test_callback_data_is_namespaced() {
+        assert_eq!(
+            CallbackAction::Language(Language::SPANISH).encode(),
+            "lang:es"
+        );
+        assert_eq!(CallbackAction::Subscribe.encode(), "digest:subscribe");
+        assert_eq!(
+            CallbackAction::parse("lang:es"),
+            Some(CallbackAction::Language(Language::SPANISH))
+        );
+        assert_eq!(
+            CallbackAction::parse("rate:12:4"),
+            Some(CallbackAction::Rate {
+                summary_id: 12,
+                rating: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_data() {
+        for data in [
+            "",
+            "subscribe",
+            "lang:",
+            "lang:xx",
+            "lang:es:extra",
+            "digest:delete",
+            "admin:broadcast",
+            ":subscribe",
+            "rate:12",
+            "rate:12:0",
+            "rate:12:6",
+            "rate:0:3",
+            "rate:-4:3",
+            "rate:x:3",
+            "rate:12:3:1",
+        ] {
+            assert_eq!(CallbackAction::parse(data), None, "{:?}", data);
+        }
+        let long = format!("lang:{}", "e".repeat(CALLBACK_DATA_LIMIT));
+        assert_eq!(CallbackAction::parse(&long), None);
+    }
+
+    #[test]
+    fn test_callback_command() {
+        assert_eq!(
+            CallbackAction::Language(Language::SPANISH).command(),
+            Some("/language es".to_string())
+        );
+        assert_eq!(
+            CallbackAction::Subscribe.command(),
+            Some("/subscribe".to_string())
+        );
+        assert_eq!(
+            CallbackAction::Unsubscribe.command(),
+            Some("/unsubscribe".to_string())
+        );
+        assert_eq!(
+            CallbackAction::Rate {
+                summary_id: 1,
+                rating: 3
+            }
+            .command(),
+            None
+        );
+    }
+
+    // ==================== Keyboard Tests ====================
+
+    #[test]
+    fn test_keyboard_serialization() {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "✅ Subscribe",
+            &CallbackAction::Subscribe,
+        )]]);
+        assert_eq!(
+            serde_json::to_value(&keyboard).unwrap(),
+            serde_json::json!({
+                "inline_keyboard": [[{"text": "✅ Subscribe", "callback_data": "digest:subscribe"}]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_keyboard_deserializes_url_buttons() {
+        let keyboard: InlineKeyboardMarkup = serde_json::from_value(serde_json::json!({
+            "inline_keyboard": [[{"text": "Open", "url": "https://example.com"}]]
+        }))
+        .unwrap();
+        assert_eq!(keyboard.inline_keyboard[0][0].callback_data, None);
+    }
+}
@@ -0,0 +1,1103 @@
+//! Telegram Bot API client.
+//!
+//! [`TelegramClient`] holds one HTTP client, the API base URL
+//! (`TELEGRAM_API_URL`, e.g. a self-hosted Bot API server) and the bot token,
+//! and exposes typed Bot API methods. Every call goes through the Telegram
+//! circuit breaker; failures are [`TelegramError`]s, whose messages never
+//! include the request URL (and so never the token).
+
+use super::{ChatMember, InlineKeyboardMarkup, Message, User};
+use crate::config::Config;
+use crate::retry::{
+    with_retry_if, CircuitBreakerConfig, CircuitBreakers, RetryConfig, RetryDecision,
+};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+/// Default Bot API server
+pub const DEFAULT_API_URL: &str = "https://api.telegram.org";
+
+#[derive(Debug, Serialize)]
+pub(super) struct SendMessageRequest {
+    pub(super) chat_id: String,
+    pub(super) text: String,
+    /// Forum topic to post in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) message_thread_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) parse_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) reply_markup: Option<InlineKeyboardMarkup>,
+}
+
+#[derive(Serialize)]
+struct EditMessageTextRequest<'a> {
+    chat_id: String,
+    message_id: i64,
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct EditMessageReplyMarkupRequest<'a> {
+    chat_id: String,
+    message_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_markup: Option<&'a InlineKeyboardMarkup>,
+}
+
+#[derive(Serialize)]
+struct AnswerCallbackQueryRequest<'a> {
+    callback_query_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct GetChatMemberRequest {
+    chat_id: String,
+    user_id: i64,
+}
+
+#[derive(Serialize)]
+struct SetWebhookRequest<'a> {
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret_token: Option<&'a str>,
+}
+
+/// Envelope of every Bot API response
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Client for one bot on one Bot API server (cheap to clone)
+#[derive(Clone)]
+pub struct TelegramClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+    circuit_breaker: CircuitBreakerConfig,
+    /// Whether sendMessage waits out flood control itself (off for fan-outs,
+    /// which requeue the message instead)
+    flood_retry: bool,
+    /// Forum topic sendMessage posts in (see [`Self::in_thread`])
+    message_thread_id: Option<i64>,
+    /// The bot's username, looked up once with getMe
+    username: Arc<OnceCell<String>>,
+}
+
+impl std::fmt::Debug for TelegramClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TelegramClient")
+            .field("base_url", &self.base_url)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+impl TelegramClient {
+    pub fn new(base_url: &str, token: &str, circuit_breaker: CircuitBreakerConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            circuit_breaker,
+            flood_retry: true,
+            message_thread_id: None,
+            username: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// A copy whose sendMessage fails on flood control instead of waiting,
+    /// so the caller can requeue the message and keep other chats going
+    pub fn without_flood_retry(&self) -> Self {
+        Self {
+            flood_retry: false,
+            ..self.clone()
+        }
+    }
+
+    /// A copy whose sendMessage posts in the given forum topic (None: the
+    /// main chat)
+    pub fn in_thread(&self, message_thread_id: Option<i64>) -> Self {
+        Self {
+            message_thread_id,
+            ..self.clone()
+        }
+    }
+
+    /// The forum topic sendMessage posts in
+    pub fn message_thread_id(&self) -> Option<i64> {
+        self.message_thread_id
+    }
+
+    /// Client for the configured bot, API server and circuit breaker settings
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            &config.telegram_api_url,
+            &config.telegram_bot_token,
+            config.circuit_breaker.clone(),
+        )
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Call a Bot API method through the Telegram circuit breaker, retrying
+    /// failures as `retry` decides
+    async fn call<B, T>(
+        &self,
+        method: &str,
+        body: &B,
+        retry: fn(&anyhow::Error) -> RetryDecision,
+    ) -> Result<T>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let url = format!("{}/bot{}/{}", self.base_url, self.token, method);
+        let operation = format!("Telegram {}", method);
+        let retry_config = RetryConfig::api_call();
+
+        CircuitBreakers::global()
+            .get(TELEGRAM_BREAKER, &self.circuit_breaker)
+            .call(
+                || {
+                    with_retry_if(
+                        &retry_config,
+                        &operation,
+                        || async {
+                            let response =
+                                self.http.post(&url).json(body).send().await.map_err(|e| {
+                                    TelegramError::Transport(e.without_url().to_string())
+                                })?;
+
+                            let status = response.status().as_u16();
+                            if !response.status().is_success() {
+                                return Err(TelegramError::from_response(response).await.into());
+                            }
+
+                            let parsed: ApiResponse<T> = response.json().await.map_err(|e| {
+                                TelegramError::InvalidResponse(e.without_url().to_string())
+                            })?;
+                            match parsed {
+                                ApiResponse {
+                                    ok: true,
+                                    result: Some(result),
+                                    ..
+                                } => Ok(result),
+                                ApiResponse {
+                                    ok: true,
+                                    result: None,
+                                    ..
+                                } => Err(TelegramError::InvalidResponse(format!(
+                                    "{} returned no result",
+                                    method
+                                ))
+                                .into()),
+                                ApiResponse { description, .. } => Err(TelegramError::Api {
+                                    status,
+                                    error_code: None,
+                                    description: description.unwrap_or_default(),
+                                    retry_after: None,
+                                }
+                                .into()),
+                            }
+                        },
+                        retry,
+                    )
+                },
+                is_dependency_failure,
+            )
+            .await
+    }
+
+    /// sendMessage. Not idempotent (a timed-out request may still have been
+    /// delivered), so the only failure retried is flood control, after the
+    /// `retry_after` Telegram asks for (see [`Self::without_flood_retry`]).
+    pub async fn send_message(
+        &self,
+        chat_id: i64,
+        text: &str,
+        parse_mode: Option<&str>,
+    ) -> Result<Message> {
+        let request = SendMessageRequest {
+            chat_id: chat_id.to_string(),
+            text: text.to_string(),
+            message_thread_id: self.message_thread_id,
+            parse_mode: parse_mode.map(str::to_string),
+            reply_markup: None,
+        };
+        self.call("sendMessage", &request, self.send_retry()).await
+    }
+
+    /// sendMessage with an inline keyboard under the message
+    pub async fn send_message_with_keyboard(
+        &self,
+        chat_id: i64,
+        text: &str,
+        parse_mode: Option<&str>,
+        keyboard: &InlineKeyboardMarkup,
+    ) -> Result<Message> {
+        let request = SendMessageRequest {
+            chat_id: chat_id.to_string(),
+            text: text.to_string(),
+            message_thread_id: self.message_thread_id,
+            parse_mode: parse_mode.map(str::to_string),
+            reply_markup: Some(keyboard.clone()),
+        };
+        self.call("sendMessage", &request, self.send_retry()).await
+    }
+
+    /// editMessageText for a message the bot sent
+    pub async fn edit_message_text(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        text: &str,
+        parse_mode: Option<&str>,
+    ) -> Result<()> {
+        let request = EditMessageTextRequest {
+            chat_id: chat_id.to_string(),
+            message_id,
+            text,
+            parse_mode,
+        };
+        // The result is the edited message, or `true` for inline messages
+        self.call::<_, serde_json::Value>("editMessageText", &request, idempotent_retry)
+            .await
+            .map(|_| ())
+    }
+
+    /// editMessageReplyMarkup: replace a message's inline keyboard, or remove
+    /// it with `None`
+    pub async fn edit_message_reply_markup(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        keyboard: Option<&InlineKeyboardMarkup>,
+    ) -> Result<()> {
+        let request = EditMessageReplyMarkupRequest {
+            chat_id: chat_id.to_string(),
+            message_id,
+            reply_markup: keyboard,
+        };
+        self.call::<_, serde_json::Value>("editMessageReplyMarkup", &request, idempotent_retry)
+            .await
+            .map(|_| ())
+    }
+
+    /// answerCallbackQuery, optionally showing `text` as a notification
+    pub async fn answer_callback_query(
+        &self,
+        callback_query_id: &str,
+        text: Option<&str>,
+    ) -> Result<()> {
+        let request = AnswerCallbackQueryRequest {
+            callback_query_id,
+            text,
+        };
+        self.call::<_, bool>("answerCallbackQuery", &request, flood_control_retry)
+            .await
+            .map(|_| ())
+    }
+
+    /// setWebhook, with the secret Telegram sends back in
+    /// `X-Telegram-Bot-Api-Secret-Token`
+    pub async fn set_webhook(&self, url: &str, secret_token: Option<&str>) -> Result<()> {
+        let request = SetWebhookRequest { url, secret_token };
+        self.call::<_, bool>("setWebhook", &request, idempotent_retry)
+            .await
+            .map(|_| ())
+    }
+
+    /// deleteWebhook
+    pub async fn delete_webhook(&self) -> Result<()> {
+        self.call::<_, bool>("deleteWebhook", &serde_json::json!({}), idempotent_retry)
+            .await
+            .map(|_| ())
+    }
+
+    /// Retry predicate for sendMessage
+    fn send_retry(&self) -> fn(&anyhow::Error) -> RetryDecision {
+        if self.flood_retry {
+            flood_control_retry
+        } else {
+            |_| RetryDecision::Stop
+        }
+    }
+
+    /// getMe: the bot's own user (checks the token)
+    pub async fn get_me(&self) -> Result<User> {
+        self.call("getMe", &serde_json::json!({}), idempotent_retry)
+            .await
+    }
+
+    /// The bot's username (without @), from getMe the first time it's needed
+    pub async fn bot_username(&self) -> Result<&str> {
+        let username = self
+            .username
+            .get_or_try_init(|| async {
+                let bot = self.get_me().await?;
+                bot.username.context("getMe returned no username")
+            })
+            .await?;
+        Ok(username)
+    }
+
+    /// getChatMember: a user's membership (and admin rights) in a chat
+    pub async fn get_chat_member(&self, chat_id: i64, user_id: i64) -> Result<ChatMember> {
+        let request = GetChatMemberRequest {
+            chat_id: chat_id.to_string(),
+            user_id,
+        };
+        self.call("getChatMember", &request, idempotent_retry).await
+    }
+}
+
+/// Circuit breaker name for the Telegram Bot API
+const TELEGRAM_BREAKER: &str = "telegram";
+
+/// Retry predicate for sendMessage: wait out flood control, fail on anything else
+fn flood_control_retry(error: &anyhow::Error) -> RetryDecision {
+    match telegram_error(error).and_then(TelegramError::retry_after) {
+        Some(delay) => RetryDecision::RetryAfter(delay),
+        None => RetryDecision::Stop,
+    }
+}
+
+/// Retry predicate for idempotent methods: wait out flood control, retry
+/// server and transport errors
+fn idempotent_retry(error: &anyhow::Error) -> RetryDecision {
+    match telegram_error(error) {
+        Some(e) => match e.retry_after() {
+            Some(delay) => RetryDecision::RetryAfter(delay),
+            None => e.is_retryable().into(),
+        },
+        None => RetryDecision::Stop,
+    }
+}
+
+/// Whether a failure says Telegram itself is unhealthy (counts toward the circuit breaker)
+fn is_dependency_failure(error: &anyhow::Error) -> bool {
+    telegram_error(error).is_some_and(TelegramError::is_retryable)
+}
+
+fn telegram_error(error: &anyhow::Error) -> Option<&TelegramError> {
+    error
+        .chain()
+        .find_map(|e| e.downcast_ref::<TelegramError>())
+}
+
+/// Error from a Telegram Bot API call
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TelegramError {
+    /// Non-success HTTP status, with the API's error details
+    #[error("Telegram API error ({status}): {description}")]
+    Api {
+        status: u16,
+        /// `error_code` from the body (usually equal to the status)
+        error_code: Option<i64>,
+        description: String,
+        /// `parameters.retry_after` on 429 responses
+        retry_after: Option<Duration>,
+    },
+    /// The request got no response (connection refused, timeout, ...)
+    #[error("Failed to send request to Telegram API: {0}")]
+    Transport(String),
+    /// A success status with a body that isn't the expected Bot API result
+    #[error("Invalid response from Telegram API: {0}")]
+    InvalidResponse(String),
+}
+
+#[derive(Deserialize)]
+struct TelegramErrorBody {
+    #[serde(default)]
+    error_code: Option<i64>,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    parameters: Option<TelegramErrorParameters>,
+}
+
+#[derive(Deserialize)]
+struct TelegramErrorParameters {
+    #[serde(default)]
+    retry_after: Option<u64>,
+}
+
+impl TelegramError {
+    /// Build an `Api` error from a status and response body.
+    /// Bodies that aren't Bot API JSON are kept verbatim as the description.
+    pub fn from_body(status: u16, body: &str) -> Self {
+        match serde_json::from_str::<TelegramErrorBody>(body) {
+            Ok(parsed) => Self::Api {
+                status,
+                error_code: parsed.error_code,
+                description: parsed.description,
+                retry_after: parsed
+                    .parameters
+                    .and_then(|p| p.retry_after)
+                    .map(Duration::from_secs),
+            },
+            Err(_) => Self::Api {
+                status,
+                error_code: None,
+                description: body.to_string(),
+                retry_after: None,
+            },
+        }
+    }
+
+    /// Read an error response
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        Self::from_body(status, &body)
+    }
+
+    /// The recipient blocked the bot or deleted their account, so the
+    /// subscriber should be removed (403 with a matching description)
+    pub fn is_recipient_gone(&self) -> bool {
+        match self {
+            Self::Api {
+                status: 403,
+                description,
+                ..
+            } => {
+                let description = description.to_lowercase();
+                description.contains("blocked by the user")
+                    || description.contains("user is deactivated")
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether sending again may succeed: flood control, server and transport errors
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Api { status, .. } => *status == 429 || *status >= 500,
+            Self::Transport(_) => true,
+            Self::InvalidResponse(_) => false,
+        }
+    }
+
+    /// How long Telegram asked us to wait before sending again
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Api { retry_after, .. } => *retry_after,
+            Self::Transport(_) | Self::InvalidResponse(_) => None,
+        }
+    }
+}
+
+/// Whether a send failed because the recipient blocked the bot or is gone
+pub fn is_recipient_gone_error(error: &anyhow::Error) -> bool {
+    telegram_error(error).is_some_and(TelegramError::is_recipient_gone)
+}
+
+/// Whether Telegram refused a send with a client error (4xx), so the message
+/// was not delivered and sending it again can't duplicate it
+pub fn is_refused_error(error: &anyhow::Error) -> bool {
+    matches!(
+        telegram_error(error),
+        Some(TelegramError::Api {
+            status: 400..=499,
+            ..
+        })
+    )
+}
+
+/// How long flood control asked us to wait, if that's why a send failed
+pub fn flood_control_delay(error: &anyhow::Error) -> Option<Duration> {
+    telegram_error(error).and_then(TelegramError::retry_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_client(server: &MockServer) -> TelegramClient {
+        TelegramClient::new(
+            &server.uri(),
+            "test-token",
+            CircuitBreakerConfig::disabled(),
+        )
+    }
+
+    fn sent_message(chat_id: i64, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "ok": true,
+            "result": {
+                "message_id": 7,
+                "chat": {"id": chat_id, "type": "private"},
+                "text": text
+            }
+        })
+    }
+
+    // ==================== Client Tests ====================
+
+    #[tokio::test]
+    async fn test_send_message_posts_to_base_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottest-token/sendMessage"))
+            .and(body_json(serde_json::json!({
+                "chat_id": "42",
+                "text": "*hi*",
+                "parse_mode": "MarkdownV2"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(sent_message(42, "hi")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let message = test_client(&server)
+            .send_message(42, "*hi*", Some("MarkdownV2"))
+            .await
+            .expect("send");
+        assert_eq!(message.message_id, 7);
+        assert_eq!(message.chat.id, 42);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_plain_text_omits_parse_mode() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottest-token/sendMessage"))
+            .and(body_json(
+                serde_json::json!({"chat_id": "42", "text": "plain *text*"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(sent_message(42, "plain")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        test_client(&server)
+            .send_message(42, "plain *text*", None)
+            .await
+            .expect("send");
+    }
+
+    #[tokio::test]
+    async fn test_send_message_does_not_retry_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let error = test_client(&server)
+            .send_message(42, "hi", None)
+            .await
+            .unwrap_err();
+        assert!(is_dependency_failure(&error));
+    }
+
+    #[tokio::test]
+    async fn test_get_me_retries_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottest-token/getMe"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/bottest-token/getMe"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": true,
+                "result": {"id": 1, "is_bot": true, "first_name": "News", "username": "news_bot"}
+            })))
+            .mount(&server)
+            .await;
+
+        let me = test_client(&server).get_me().await.expect("getMe");
+        assert_eq!(me.username.as_deref(), Some("news_bot"));
+    }
+
+    #[tokio::test]
+    async fn test_bot_username_is_looked_up_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottest-token/getMe"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": true,
+                "result": {"id": 1, "is_bot": true, "first_name": "News", "username": "news_bot"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        assert_eq!(client.bot_username().await.expect("username"), "news_bot");
+        // Copies share the looked-up name
+        let copy = client.in_thread(Some(3));
+        assert_eq!(copy.bot_username().await.expect("username"), "news_bot");
+    }
+
+    #[tokio::test]
+    async fn test_group_methods_request_bodies() {
+        let server = MockServer::start().await;
+        Mock::given(path("/bottest-token/getChatMember"))
+            .and(body_json(
+                serde_json::json!({"chat_id": "-100", "user_id": 42}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": true,
+                "result": {"status": "creator", "user": {"id": 42, "first_name": "Ana"}}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/bottest-token/sendMessage"))
+            .and(body_json(
+                serde_json::json!({"chat_id": "-100", "text": "hi", "message_thread_id": 9}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(sent_message(-100, "hi")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        let member = client.get_chat_member(-100, 42).await.expect("member");
+        assert!(member.status.is_admin());
+        assert_eq!(member.user.id, 42);
+
+        let in_topic = client.in_thread(Some(9));
+        assert_eq!(in_topic.message_thread_id(), Some(9));
+        in_topic.send_message(-100, "hi", None).await.expect("send");
+        assert_eq!(client.message_thread_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_typed_methods_request_bodies() {
+        let server = MockServer::start().await;
+        let ok = ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({"ok": true, "result": true}));
+        Mock::given(path("/bottest-token/editMessageText"))
+            .and(body_json(
+                serde_json::json!({"chat_id": "42", "message_id": 7, "text": "edited"}),
+            ))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/bottest-token/answerCallbackQuery"))
+            .and(body_json(
+                serde_json::json!({"callback_query_id": "abc", "text": "Thanks"}),
+            ))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/bottest-token/setWebhook"))
+            .and(body_json(serde_json::json!({
+                "url": "https://bot.example.com/webhook",
+                "secret_token": "s3cret"
+            })))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/bottest-token/deleteWebhook"))
+            .respond_with(ok)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        client
+            .edit_message_text(42, 7, "edited", None)
+            .await
+            .expect("edit");
+        client
+            .answer_callback_query("abc", Some("Thanks"))
+            .await
+            .expect("answer");
+        client
+            .set_webhook("https://bot.example.com/webhook", Some("s3cret"))
+            .await
+            .expect("setWebhook");
+        client.delete_webhook().await.expect("deleteWebhook");
+    }
+
+    #[tokio::test]
+    async fn test_keyboard_methods_request_bodies() {
+        use crate::telegram::{CallbackAction, InlineKeyboardButton};
+
+        let server = MockServer::start().await;
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Subscribe",
+            &CallbackAction::Subscribe,
+        )]]);
+        let keyboard_json = serde_json::json!({
+            "inline_keyboard": [[{"text": "Subscribe", "callback_data": "digest:subscribe"}]]
+        });
+        Mock::given(path("/bottest-token/sendMessage"))
+            .and(body_json(serde_json::json!({
+                "chat_id": "42",
+                "text": "hi",
+                "reply_markup": keyboard_json
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(sent_message(42, "hi")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/bottest-token/editMessageReplyMarkup"))
+            .and(body_json(serde_json::json!({
+                "chat_id": "42",
+                "message_id": 7,
+                "reply_markup": keyboard_json
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"ok": true, "result": true})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Without a keyboard the markup is removed
+        Mock::given(path("/bottest-token/editMessageReplyMarkup"))
+            .and(body_json(
+                serde_json::json!({"chat_id": "42", "message_id": 8}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"ok": true, "result": true})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server);
+        client
+            .send_message_with_keyboard(42, "hi", None, &keyboard)
+            .await
+            .expect("send");
+        client
+            .edit_message_reply_markup(42, 7, Some(&keyboard))
+            .await
+            .expect("edit");
+        client
+            .edit_message_reply_markup(42, 8, None)
+            .await
+            .expect("remove");
+    }
+
+    #[tokio::test]
+    async fn test_not_ok_response_is_api_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": false,
+                "description": "Bad Request: chat not found"
+            })))
+            .mount(&server)
+            .await;
+
+        let error = test_client(&server)
+            .send_message(42, "hi", None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("chat not found"));
+    }
+
+    #[tokio::test]
+    async fn test_errors_do_not_leak_token() {
+        // Nothing listens on port 1
+        let client = TelegramClient::new(
+            "http://127.0.0.1:1",
+            "123:secret-token",
+            CircuitBreakerConfig::disabled(),
+        );
+
+        let error = client.send_message(42, "hi", None).await.unwrap_err();
+        assert!(matches!(
+            telegram_error(&error),
+            Some(TelegramError::Transport(_))
+        ));
+        assert!(!format!("{:#}", error).contains("secret-token"));
+        assert!(!format!("{:?}", client).contains("secret-token"));
+    }
+
+    #[test]
+    fn test_base_url_trailing_slash() {
+        let client = TelegramClient::new(
+            "http://localhost:8081/",
+            "t",
+            CircuitBreakerConfig::disabled(),
+        );
+        assert_eq!(client.base_url(), "http://localhost:8081");
+    }
+
+    // ==================== SendMessageRequest Tests ====================
+
+    #[test]
+    fn test_send_message_request_serialization() {
+        let request = SendMessageRequest {
+            chat_id: "123456789".to_string(),
+            text: "Hello, World!".to_string(),
+            message_thread_id: None,
+            parse_mode: Some("MarkdownV2".to_string()),
+            reply_markup: None,
+        };
+
+        let json = serde_json::to_string(&request).expect("Should serialize");
+        assert!(json.contains("123456789"));
+        assert!(json.contains("Hello, World!"));
+        assert!(json.contains("MarkdownV2"));
+    }
+
+    #[test]
+    fn test_send_message_request_with_markdown_content() {
+        let request = SendMessageRequest {
+            chat_id: "123".to_string(),
+            text: "*Bold* and _italic_".to_string(),
+            message_thread_id: None,
+            parse_mode: Some("MarkdownV2".to_string()),
+            reply_markup: None,
+        };
+
+        let json = serde_json::to_string(&request).expect("Should serialize");
+        assert!(json.contains("*Bold*"));
+        assert!(json.contains("_italic_"));
+    }
+
+    #[test]
+    fn test_send_message_request_with_special_characters() {
+        let request = SendMessageRequest {
+            chat_id: "123".to_string(),
+            text: "Text with \"quotes\" and \\ backslash".to_string(),
+            message_thread_id: None,
+            parse_mode: Some("MarkdownV2".to_string()),
+            reply_markup: None,
+        };
+
+        let json = serde_json::to_string(&request).expect("Should serialize");
+        // JSON should escape special characters
+        assert!(json.contains("\\\"quotes\\\"") || json.contains("quotes"));
+    }
+
+    #[test]
+    fn test_send_message_request_with_newlines() {
+        let request = SendMessageRequest {
+            chat_id: "123".to_string(),
+            text: "Line 1\nLine 2\nLine 3".to_string(),
+            message_thread_id: None,
+            parse_mode: Some("MarkdownV2".to_string()),
+            reply_markup: None,
+        };
+
+        let json = serde_json::to_string(&request).expect("Should serialize");
+        // Newlines should be escaped in JSON
+        assert!(json.contains("\\n"));
+    }
+
+    // ==================== Typed Error Tests ====================
+
+    #[test]
+    fn test_telegram_error_parses_bot_api_body() {
+        let body = r#"{"ok":false,"error_code":429,"description":"Too Many Requests: retry after 35","parameters":{"retry_after":35}}"#;
+        let error = TelegramError::from_body(429, body);
+
+        assert_eq!(
+            error,
+            TelegramError::Api {
+                status: 429,
+                error_code: Some(429),
+                description: "Too Many Requests: retry after 35".to_string(),
+                retry_after: Some(Duration::from_secs(35)),
+            }
+        );
+        assert!(error.is_retryable());
+        assert!(!error.is_recipient_gone());
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(35)));
+    }
+
+    #[test]
+    fn test_telegram_error_keeps_unstructured_body() {
+        let error = TelegramError::from_body(502, "<html>Bad Gateway</html>");
+
+        assert_eq!(
+            error,
+            TelegramError::Api {
+                status: 502,
+                error_code: None,
+                description: "<html>Bad Gateway</html>".to_string(),
+                retry_after: None,
+            }
+        );
+        assert!(error.is_retryable());
+        assert_eq!(
+            error.to_string(),
+            "Telegram API error (502): <html>Bad Gateway</html>"
+        );
+    }
+
+    #[test]
+    fn test_telegram_error_blocked_is_recipient_gone() {
+        let error = TelegramError::from_body(
+            403,
+            r#"{"ok":false,"error_code":403,"description":"Forbidden: bot was blocked by the user"}"#,
+        );
+
+        assert!(error.is_recipient_gone());
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_telegram_error_client_errors_not_retryable() {
+        for (status, description) in [
+            (400, "Bad Request: chat not found"),
+            (401, "Unauthorized"),
+            (403, "Forbidden: bot was kicked from the group chat"),
+        ] {
+            let body = format!(
+                r#"{{"ok":false,"error_code":{},"description":"{}"}}"#,
+                status, description
+            );
+            let error = TelegramError::from_body(status, &body);
+            assert!(!error.is_retryable(), "{} should not be retried", status);
+            assert!(!error.is_recipient_gone(), "{} should not remove", status);
+        }
+    }
+
+    #[test]
+    fn test_telegram_error_server_and_transport_retryable() {
+        let server = TelegramError::from_body(
+            500,
+            r#"{"ok":false,"error_code":500,"description":"Internal Server Error"}"#,
+        );
+        let transport = TelegramError::Transport("connection refused".to_string());
+
+        assert!(server.is_retryable());
+        assert!(transport.is_retryable());
+        assert!(!transport.is_recipient_gone());
+        assert_eq!(transport.retry_after(), None);
+    }
+
+    #[test]
+    fn test_flood_control_retry_waits_for_retry_after() {
+        let flood = anyhow::Error::new(TelegramError::from_body(
+            429,
+            r#"{"ok":false,"error_code":429,"description":"Too Many Requests: retry after 3","parameters":{"retry_after":3}}"#,
+        ));
+        assert_eq!(
+            flood_control_retry(&flood),
+            RetryDecision::RetryAfter(Duration::from_secs(3))
+        );
+
+        // Not idempotent: transport and server errors aren't retried
+        let transport = anyhow::Error::new(TelegramError::Transport("timed out".to_string()));
+        assert_eq!(flood_control_retry(&transport), RetryDecision::Stop);
+        let server = anyhow::Error::new(TelegramError::from_body(502, "Bad Gateway"));
+        assert_eq!(flood_control_retry(&server), RetryDecision::Stop);
+    }
+
+    #[tokio::test]
+    async fn test_without_flood_retry_returns_flood_control() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).set_body_string(
+                r#"{"ok":false,"error_code":429,"description":"Too Many Requests: retry after 7","parameters":{"retry_after":7}}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let error = test_client(&server)
+            .without_flood_retry()
+            .send_message(42, "hi", None)
+            .await
+            .unwrap_err();
+        assert_eq!(flood_control_delay(&error), Some(Duration::from_secs(7)));
+        assert_eq!(
+            flood_control_delay(&anyhow::anyhow!("Invalid chat ID")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_refused_error() {
+        let refused = anyhow::Error::new(TelegramError::from_body(
+            400,
+            r#"{"ok":false,"error_code":400,"description":"Bad Request: chat not found"}"#,
+        ))
+        .context("Failed to send digest to 42");
+        assert!(is_refused_error(&refused));
+
+        // No answer, or a server error: the message may have arrived
+        let transport = anyhow::Error::new(TelegramError::Transport("timed out".to_string()));
+        let server = anyhow::Error::new(TelegramError::from_body(502, "Bad Gateway"));
+        assert!(!is_refused_error(&transport));
+        assert!(!is_refused_error(&server));
+        assert!(!is_refused_error(&anyhow::anyhow!("Database is down")));
+    }
+
+    #[test]
+    fn test_is_dependency_failure() {
+        let server = anyhow::Error::new(TelegramError::from_body(500, "Internal Server Error"));
+        let blocked = anyhow::Error::new(TelegramError::from_body(
+            403,
+            r#"{"ok":false,"error_code":403,"description":"Forbidden: bot was blocked by the user"}"#,
+        ));
+
+        assert!(is_dependency_failure(&server));
+        assert!(!is_dependency_failure(&blocked));
+        assert!(!is_dependency_failure(&anyhow::anyhow!("Invalid chat ID")));
+    }
+
+    #[test]
+    fn test_is_recipient_gone_error_through_context() {
+        let error = anyhow::Error::new(TelegramError::from_body(
+            403,
+            r#"{"ok":false,"error_code":403,"description":"Forbidden: user is deactivated"}"#,
+        ))
+        .context("Failed to send digest to 42");
+
+        assert!(is_recipient_gone_error(&error));
+        assert!(!is_recipient_gone_error(&anyhow::anyhow!(
+            "403 blocked by the user"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_telegram_error_from_response() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(403).set_body_string(
+                r#"{"ok":false,"error_code":403,"description":"Forbidden: bot was blocked by the user"}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let response = reqwest::Client::new()
+            .post(server.uri())
+            .send()
+            .await
+            .unwrap();
+        let error = TelegramError::from_response(response).await;
+
+        assert_eq!(error.retry_after(), None);
+        assert!(error.is_recipient_gone());
+    }
+}
@@ -0,0 +1,1003 @@
+//! Rate-limited fan-out of messages to many chats.
+//!
+//! [`deliver`] sends a batch of [`Delivery`]s with up to
+//! [`DeliveryLimits::concurrency`] chats in flight. Every message takes a
+//! token from a global bucket (about 30 messages/s, Telegram's broadcast
+//! limit), and messages to one chat are spaced by
+//! [`DeliveryLimits::per_chat_interval`] ([`DeliveryLimits::per_group_interval`]
+//! for groups and channels, which Telegram allows 20 messages a minute), so
+//! the parts of a split digest still arrive in order. A send refused by flood control (429) pauses the bucket
+//! and the chat for the `retry_after` Telegram asks for, and the delivery goes
+//! back to the end of the queue, resuming at the part that failed.
+//!
+//! A [`DeliveryLedger`] claims each chat before its first message and records
+//! how the delivery ended; a chat it has already seen is skipped, so a rerun
+//! never sends a chat the same messages twice.
+//!
+//! Each fan-out's progress is logged as it runs and kept in [`FanOuts`] for
+//! the `/deliveries` endpoint.
+
+use super::client::flood_control_delay;
+use super::{InlineKeyboardMarkup, TelegramClient};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Default global send rate (Telegram allows about 30 messages per second)
+pub const DEFAULT_MESSAGES_PER_SECOND: u32 = 30;
+
+/// Default number of chats sent to at once
+pub const DEFAULT_DELIVERY_CONCURRENCY: usize = 8;
+
+/// Default time after which an unfinished ledger claim can be taken again
+pub const DEFAULT_CLAIM_TIMEOUT_SECS: u64 = 3600;
+
+/// Fan-outs kept for the `/deliveries` endpoint
+const RECENT_FAN_OUTS: usize = 10;
+
+/// Limits of one fan-out
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryLimits {
+    /// Messages per second across all chats
+    pub messages_per_second: u32,
+    /// Chats sent to at once
+    pub concurrency: usize,
+    /// Minimum time between two messages to the same private chat
+    pub per_chat_interval: Duration,
+    /// Minimum time between two messages to the same group or channel
+    pub per_group_interval: Duration,
+    /// Times a delivery is requeued after flood control before it fails
+    pub max_requeues: u32,
+    /// How long a ledger claim with no recorded outcome holds before
+    /// another run may send to the chat again
+    pub claim_timeout: Duration,
+}
+
+impl Default for DeliveryLimits {
+    fn default() -> Self {
+        Self {
+            messages_per_second: DEFAULT_MESSAGES_PER_SECOND,
+            concurrency: DEFAULT_DELIVERY_CONCURRENCY,
+            per_chat_interval: Duration::from_secs(1),
+            per_group_interval: Duration::from_secs(3),
+            max_requeues: 5,
+            claim_timeout: Duration::from_secs(DEFAULT_CLAIM_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// Messages for one chat, sent in order
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub chat_id: i64,
+    pub messages: Vec<String>,
+    pub parse_mode: Option<String>,
+    /// Forum topic to post in
+    pub message_thread_id: Option<i64>,
+    /// Inline keyboard under the last message
+    pub keyboard: Option<InlineKeyboardMarkup>,
+}
+
+/// Global token bucket. Holds at most one second of tokens; a pause empties
+/// it until the pause is over.
+struct TokenBucket {
+    rate: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    /// When tokens were last added (in the future while paused)
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(messages_per_second: u32) -> Self {
+        Self {
+            rate: f64::from(messages_per_second.max(1)),
+            state: Mutex::new(BucketState {
+                tokens: 1.0,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait for a token
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                if state.refilled_at > now {
+                    state.refilled_at - now
+                } else {
+                    let elapsed = (now - state.refilled_at).as_secs_f64();
+                    state.tokens = (state.tokens + elapsed * self.rate).min(self.rate);
+                    state.refilled_at = now;
+                    if state.tokens >= 1.0 {
+                        state.tokens -= 1.0;
+                        return;
+                    }
+                    Duration::from_secs_f64((1.0 - state.tokens) / self.rate)
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Hand out no tokens for `delay`
+    fn pause(&self, delay: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let until = Instant::now() + delay;
+        if until > state.refilled_at {
+            state.tokens = 0.0;
+            state.refilled_at = until;
+        }
+    }
+}
+
+/// Spaces the messages sent to each chat
+struct ChatPacer {
+    interval: Duration,
+    group_interval: Duration,
+    next: Mutex<HashMap<i64, Instant>>,
+}
+
+impl ChatPacer {
+    fn new(interval: Duration, group_interval: Duration) -> Self {
+        Self {
+            interval,
+            group_interval,
+            next: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Groups and channels have negative chat ids
+    fn interval_for(&self, chat_id: i64) -> Duration {
+        if chat_id < 0 {
+            self.group_interval
+        } else {
+            self.interval
+        }
+    }
+
+    /// Wait for the chat's next slot, and reserve it
+    async fn wait(&self, chat_id: i64) {
+        let at = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+            let at = next.get(&chat_id).copied().filter(|at| *at > now);
+            let at = at.unwrap_or(now);
+            next.insert(chat_id, at + self.interval_for(chat_id));
+            at
+        };
+        tokio::time::sleep_until(at).await;
+    }
+
+    /// Send nothing to the chat for `delay`
+    fn pause(&self, chat_id: i64, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut next = self.next.lock().unwrap();
+        let entry = next.entry(chat_id).or_insert(until);
+        *entry = (*entry).max(until);
+    }
+}
+
+/// How one delivery ended
+#[derive(Debug)]
+pub enum DeliveryOutcome {
+    /// Every message was sent; the id of the last one
+    Sent { message_id: i64 },
+    /// The ledger says an earlier run already took this chat
+    Skipped,
+    /// Sending stopped at an error, after `delivered` messages were sent
+    Failed {
+        error: anyhow::Error,
+        delivered: usize,
+    },
+}
+
+impl DeliveryOutcome {
+    pub fn is_sent(&self) -> bool {
+        matches!(self, Self::Sent { .. })
+    }
+}
+
+/// Where a fan-out records each chat, so that no chat is sent the same
+/// messages twice, even across runs
+pub trait DeliveryLedger: Sync {
+    /// Take the chat before its first message is sent; `false` if an earlier
+    /// run already took it (the delivery is skipped)
+    fn claim(&self, chat_id: i64) -> impl Future<Output = Result<bool>> + Send;
+
+    /// Record how a claimed delivery ended
+    fn record(
+        &self,
+        chat_id: i64,
+        outcome: &DeliveryOutcome,
+    ) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Ledger for fan-outs with nothing to record (broadcasts)
+pub struct NoLedger;
+
+impl DeliveryLedger for NoLedger {
+    async fn claim(&self, _chat_id: i64) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn record(&self, _chat_id: i64, _outcome: &DeliveryOutcome) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A delivery in the queue
+struct Job {
+    index: usize,
+    delivery: Delivery,
+    /// First message not sent yet
+    next_message: usize,
+    /// Id of the last message sent
+    message_id: Option<i64>,
+    requeues: u32,
+}
+
+/// State shared by the workers of one fan-out
+struct FanOut<'a, L> {
+    telegram: TelegramClient,
+    limits: &'a DeliveryLimits,
+    ledger: &'a L,
+    bucket: TokenBucket,
+    pacer: ChatPacer,
+    queue: Mutex<VecDeque<Job>>,
+    outcomes: Mutex<Vec<Option<DeliveryOutcome>>>,
+    progress: Arc<FanOutProgress>,
+}
+
+impl<L: DeliveryLedger> FanOut<'_, L> {
+    async fn worker(&self) {
+        loop {
+            let Some(mut job) = self.queue.lock().unwrap().pop_front() else {
+                break;
+            };
+            let chat_id = job.delivery.chat_id;
+            if job.next_message == 0 && job.requeues == 0 {
+                match self.ledger.claim(chat_id).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        self.progress.skipped.fetch_add(1, Ordering::Relaxed);
+                        self.finish(&job, DeliveryOutcome::Skipped);
+                        continue;
+                    }
+                    Err(error) => {
+                        // Nothing was claimed, so there is nothing to record
+                        self.progress.failed.fetch_add(1, Ordering::Relaxed);
+                        self.finish(
+                            &job,
+                            DeliveryOutcome::Failed {
+                                error,
+                                delivered: 0,
+                            },
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            let outcome = match self.send(&mut job).await {
+                Ok(message_id) => {
+                    self.progress.sent.fetch_add(1, Ordering::Relaxed);
+                    DeliveryOutcome::Sent { message_id }
+                }
+                Err(e) => match flood_control_delay(&e) {
+                    Some(delay) if job.requeues < self.limits.max_requeues => {
+                        warn!(
+                            "Flood control for {}, requeued (retry after {:?})",
+                            chat_id, delay
+                        );
+                        job.requeues += 1;
+                        self.bucket.pause(delay);
+                        self.pacer.pause(chat_id, delay);
+                        self.progress.requeued.fetch_add(1, Ordering::Relaxed);
+                        self.queue.lock().unwrap().push_back(job);
+                        continue;
+                    }
+                    _ => {
+                        self.progress.failed.fetch_add(1, Ordering::Relaxed);
+                        DeliveryOutcome::Failed {
+                            error: e,
+                            delivered: job.next_message,
+                        }
+                    }
+                },
+            };
+            if let Err(e) = self.ledger.record(chat_id, &outcome).await {
+                warn!("Failed to record delivery to {}: {}", chat_id, e);
+            }
+            self.finish(&job, outcome);
+        }
+    }
+
+    fn finish(&self, job: &Job, outcome: DeliveryOutcome) {
+        self.outcomes.lock().unwrap()[job.index] = Some(outcome);
+        self.progress.log_if_due();
+    }
+
+    /// Send the job's remaining messages in order, stopping at the first
+    /// failure. Returns the id of the last message.
+    async fn send(&self, job: &mut Job) -> Result<i64> {
+        let delivery = &job.delivery;
+        let parse_mode = delivery.parse_mode.as_deref();
+        let telegram = self.telegram.in_thread(delivery.message_thread_id);
+        while job.next_message < delivery.messages.len() {
+            let i = job.next_message;
+            self.pacer.wait(delivery.chat_id).await;
+            self.bucket.acquire().await;
+            let text = &delivery.messages[i];
+            let message = match delivery
+                .keyboard
+                .as_ref()
+                .filter(|_| i + 1 == delivery.messages.len())
+            {
+                Some(keyboard) => {
+                    telegram
+                        .send_message_with_keyboard(delivery.chat_id, text, parse_mode, keyboard)
+                        .await?
+                }
+                None => {
+                    telegram
+                        .send_message(delivery.chat_id, text, parse_mode)
+                        .await?
+                }
+            };
+            self.progress.messages.fetch_add(1, Ordering::Relaxed);
+            job.message_id = Some(message.message_id);
+            job.next_message += 1;
+        }
+        job.message_id.context("Delivery has no messages")
+    }
+}
+
+/// Send every delivery within `limits`, claiming each chat in `ledger` first
+/// and recording how it ended, and reporting progress under `name`. Returns
+/// one outcome per delivery, in the order given.
+pub async fn deliver<L: DeliveryLedger>(
+    telegram: &TelegramClient,
+    limits: &DeliveryLimits,
+    ledger: &L,
+    name: &str,
+    deliveries: Vec<Delivery>,
+) -> Vec<DeliveryOutcome> {
+    let progress = FanOuts::global().start(name, deliveries.len());
+    run(telegram, limits, ledger, progress, deliveries).await
+}
+
+async fn run<L: DeliveryLedger>(
+    telegram: &TelegramClient,
+    limits: &DeliveryLimits,
+    ledger: &L,
+    progress: Arc<FanOutProgress>,
+    deliveries: Vec<Delivery>,
+) -> Vec<DeliveryOutcome> {
+    let total = deliveries.len();
+    let fan_out = FanOut {
+        telegram: telegram.without_flood_retry(),
+        limits,
+        ledger,
+        bucket: TokenBucket::new(limits.messages_per_second),
+        pacer: ChatPacer::new(limits.per_chat_interval, limits.per_group_interval),
+        queue: Mutex::new(
+            deliveries
+                .into_iter()
+                .enumerate()
+                .map(|(index, delivery)| Job {
+                    index,
+                    delivery,
+                    next_message: 0,
+                    message_id: None,
+                    requeues: 0,
+                })
+                .collect(),
+        ),
+        outcomes: Mutex::new((0..total).map(|_| None).collect()),
+        progress: progress.clone(),
+    };
+
+    let workers = limits.concurrency.clamp(1, total.max(1));
+    futures::future::join_all((0..workers).map(|_| fan_out.worker())).await;
+
+    progress.finish();
+    fan_out
+        .outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|outcome| {
+            outcome.unwrap_or_else(|| DeliveryOutcome::Failed {
+                error: anyhow::anyhow!("Delivery was not attempted"),
+                delivered: 0,
+            })
+        })
+        .collect()
+}
+
+/// Live counters of one fan-out
+pub struct FanOutProgress {
+    name: String,
+    total: usize,
+    sent: AtomicUsize,
+    failed: AtomicUsize,
+    /// Chats an earlier run already took
+    skipped: AtomicUsize,
+    requeued: AtomicUsize,
+    /// Messages sent (a split digest is several)
+    messages: AtomicUsize,
+    started: Instant,
+    started_at: DateTime<Utc>,
+    finished: Mutex<Option<(Instant, DateTime<Utc>)>>,
+}
+
+impl FanOutProgress {
+    fn new(name: &str, total: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            total,
+            sent: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            skipped: AtomicUsize::new(0),
+            requeued: AtomicUsize::new(0),
+            messages: AtomicUsize::new(0),
+            started: Instant::now(),
+            started_at: Utc::now(),
+            finished: Mutex::new(None),
+        }
+    }
+
+    /// Log progress at every tenth of the deliveries
+    fn log_if_due(&self) {
+        let done = self.sent.load(Ordering::Relaxed)
+            + self.failed.load(Ordering::Relaxed)
+            + self.skipped.load(Ordering::Relaxed);
+        let step = (self.total / 10).max(1);
+        if done.is_multiple_of(step) || done == self.total {
+            let report = self.report();
+            info!(
+                "{}: {}/{} delivered ({} failed, {} skipped, {} requeued, {:.1} msg/s)",
+                report.name,
+                report.sent,
+                report.total,
+                report.failed,
+                report.skipped,
+                report.requeued,
+                report.messages_per_second
+            );
+        }
+    }
+
+    fn finish(&self) {
+        *self.finished.lock().unwrap() = Some((Instant::now(), Utc::now()));
+    }
+
+    pub fn report(&self) -> FanOutReport {
+        let sent = self.sent.load(Ordering::Relaxed);
+        let failed = self.failed.load(Ordering::Relaxed);
+        let skipped = self.skipped.load(Ordering::Relaxed);
+        let finished = *self.finished.lock().unwrap();
+        let elapsed = finished
+            .map(|(at, _)| at)
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.started)
+            .as_secs_f64();
+        let messages = self.messages.load(Ordering::Relaxed);
+        FanOutReport {
+            name: self.name.clone(),
+            total: self.total,
+            sent,
+            failed,
+            skipped,
+            requeued: self.requeued.load(Ordering::Relaxed),
+            pending: self.total.saturating_sub(sent + failed + skipped),
+            messages,
+            messages_per_second: if elapsed > 0.0 {
+                messages as f64 / elapsed
+            } else {
+                0.0
+            },
+            started_at: self.started_at,
+            finished_at: finished.map(|(_, at)| at),
+        }
+    }
+}
+
+/// Snapshot of one fan-out
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FanOutReport {
+    pub name: String,
+    /// Chats to deliver to
+    pub total: usize,
+    pub sent: usize,
+    pub failed: usize,
+    /// Chats an earlier run already took
+    pub skipped: usize,
+    /// Times a delivery went back to the queue after flood control
+    pub requeued: usize,
+    pub pending: usize,
+    pub messages: usize,
+    pub messages_per_second: f64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// The most recent fan-outs, running or finished
+pub struct FanOuts {
+    recent: Mutex<VecDeque<Arc<FanOutProgress>>>,
+}
+
+static FAN_OUTS: OnceLock<FanOuts> = OnceLock::new();
+
+impl FanOuts {
+    /// Get the global fan-out registry.
+    pub fn global() -> &'static FanOuts {
+        FAN_OUTS.get_or_init(|| FanOuts {
+            recent: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    fn start(&self, name: &str, total: usize) -> Arc<FanOutProgress> {
+        let progress = Arc::new(FanOutProgress::new(name, total));
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() == RECENT_FAN_OUTS {
+            recent.pop_front();
+        }
+        recent.push_back(progress.clone());
+        progress
+    }
+
+    /// Reports of the recent fan-outs, newest first
+    pub fn report(&self) -> Vec<FanOutReport> {
+        let recent = self.recent.lock().unwrap();
+        recent
+            .iter()
+            .rev()
+            .map(|progress| progress.report())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::CircuitBreakerConfig;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_client(server: &MockServer) -> TelegramClient {
+        TelegramClient::new(
+            &server.uri(),
+            "test-token",
+            CircuitBreakerConfig::disabled(),
+        )
+    }
+
+    fn sent() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "ok": true,
+            "result": {"message_id": 1, "chat": {"id": 1, "type": "private"}}
+        }))
+    }
+
+    fn flood_control(retry_after: u64) -> ResponseTemplate {
+        ResponseTemplate::new(429).set_body_json(serde_json::json!({
+            "ok": false,
+            "error_code": 429,
+            "description": format!("Too Many Requests: retry after {}", retry_after),
+            "parameters": {"retry_after": retry_after}
+        }))
+    }
+
+    /// Run a fan-out outside the global registry, so its report can't be
+    /// pushed out by fan-outs of other tests
+    async fn run_test(
+        server: &MockServer,
+        limits: &DeliveryLimits,
+        deliveries: Vec<Delivery>,
+    ) -> (Vec<DeliveryOutcome>, FanOutReport) {
+        run_with_ledger(server, limits, &NoLedger, deliveries).await
+    }
+
+    async fn run_with_ledger<L: DeliveryLedger>(
+        server: &MockServer,
+        limits: &DeliveryLimits,
+        ledger: &L,
+        deliveries: Vec<Delivery>,
+    ) -> (Vec<DeliveryOutcome>, FanOutReport) {
+        let progress = Arc::new(FanOutProgress::new("test", deliveries.len()));
+        let outcomes = run(
+            &test_client(server),
+            limits,
+            ledger,
+            progress.clone(),
+            deliveries,
+        )
+        .await;
+        (outcomes, progress.report())
+    }
+
+    /// In-memory ledger: chats in `taken` can't be claimed
+    #[derive(Default)]
+    struct TestLedger {
+        taken: Mutex<Vec<i64>>,
+        claims: Mutex<Vec<i64>>,
+        records: Mutex<Vec<(i64, String)>>,
+    }
+
+    impl DeliveryLedger for TestLedger {
+        async fn claim(&self, chat_id: i64) -> Result<bool> {
+            self.claims.lock().unwrap().push(chat_id);
+            let mut taken = self.taken.lock().unwrap();
+            if taken.contains(&chat_id) {
+                return Ok(false);
+            }
+            taken.push(chat_id);
+            Ok(true)
+        }
+
+        async fn record(&self, chat_id: i64, outcome: &DeliveryOutcome) -> Result<()> {
+            let outcome = match outcome {
+                DeliveryOutcome::Sent { message_id } => format!("sent {}", message_id),
+                DeliveryOutcome::Skipped => "skipped".to_string(),
+                DeliveryOutcome::Failed { delivered, .. } => format!("failed after {}", delivered),
+            };
+            self.records.lock().unwrap().push((chat_id, outcome));
+            Ok(())
+        }
+    }
+
+    fn delivery(chat_id: i64, messages: &[&str]) -> Delivery {
+        Delivery {
+            chat_id,
+            messages: messages.iter().map(|m| m.to_string()).collect(),
+            parse_mode: None,
+            message_thread_id: None,
+            keyboard: None,
+        }
+    }
+
+    async fn texts_to(server: &MockServer, chat_id: i64) -> Vec<String> {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter_map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).ok())
+            .filter(|body| body["chat_id"] == serde_json::json!(chat_id.to_string()))
+            .map(|body| body["text"].as_str().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    // ==================== Token Bucket Tests ====================
+
+    #[tokio::test]
+    async fn test_token_bucket_paces_to_rate() {
+        let bucket = TokenBucket::new(50);
+        let start = Instant::now();
+        for _ in 0..11 {
+            bucket.acquire().await;
+        }
+        // One token to start, then 10 more at 50/s
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(190), "got {:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(400), "got {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_pause() {
+        let bucket = TokenBucket::new(1000);
+        bucket.pause(Duration::from_millis(150));
+        let start = Instant::now();
+        bucket.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_chat_pacer_spaces_one_chat_only() {
+        let pacer = ChatPacer::new(Duration::from_millis(100), Duration::from_millis(100));
+        let start = Instant::now();
+        pacer.wait(1).await;
+        pacer.wait(2).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+        pacer.wait(1).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_chat_pacer_spaces_groups_further() {
+        let pacer = ChatPacer::new(Duration::from_millis(20), Duration::from_millis(150));
+        let start = Instant::now();
+        pacer.wait(1).await;
+        pacer.wait(1).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+        // Groups and channels have negative ids
+        pacer.wait(-1).await;
+        pacer.wait(-1).await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    // ==================== Fan-out Tests ====================
+
+    #[tokio::test]
+    async fn test_deliver_sends_parts_in_order_with_keyboard_on_last() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottest-token/sendMessage"))
+            .respond_with(sent())
+            .mount(&server)
+            .await;
+
+        let keyboard = InlineKeyboardMarkup::new(vec![]);
+        let deliveries = vec![
+            Delivery {
+                keyboard: Some(keyboard),
+                ..delivery(1, &["1/3", "2/3", "3/3"])
+            },
+            delivery(2, &["only"]),
+        ];
+        let limits = DeliveryLimits {
+            per_chat_interval: Duration::from_millis(50),
+            ..DeliveryLimits::default()
+        };
+        let (results, _) = run_test(&server, &limits, deliveries).await;
+
+        assert!(results.iter().all(DeliveryOutcome::is_sent));
+        assert_eq!(texts_to(&server, 1).await, vec!["1/3", "2/3", "3/3"]);
+        let bodies: Vec<serde_json::Value> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice(&r.body).unwrap())
+            .filter(|body: &serde_json::Value| body["chat_id"] == "1")
+            .collect();
+        assert!(bodies[..2].iter().all(|b| b.get("reply_markup").is_none()));
+        assert!(bodies[2].get("reply_markup").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_posts_in_forum_topic() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/bottest-token/sendMessage"))
+            .respond_with(sent())
+            .mount(&server)
+            .await;
+
+        let deliveries = vec![
+            Delivery {
+                message_thread_id: Some(9),
+                ..delivery(-100, &["topic"])
+            },
+            delivery(-200, &["main chat"]),
+        ];
+        let (results, _) = run_test(&server, &DeliveryLimits::default(), deliveries).await;
+
+        assert!(results.iter().all(DeliveryOutcome::is_sent));
+        let bodies: Vec<serde_json::Value> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice(&r.body).unwrap())
+            .collect();
+        let topic = bodies.iter().find(|b| b["chat_id"] == "-100").unwrap();
+        assert_eq!(topic["message_thread_id"], 9);
+        let main = bodies.iter().find(|b| b["chat_id"] == "-200").unwrap();
+        assert!(main.get("message_thread_id").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_requeues_after_flood_control() {
+        let server = MockServer::start().await;
+        // The second part to chat 1 is refused once
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"chat_id": "1", "text": "2/2"}),
+            ))
+            .respond_with(flood_control(1))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(sent())
+            .mount(&server)
+            .await;
+
+        let deliveries = vec![delivery(1, &["1/2", "2/2"]), delivery(2, &["hi"])];
+        let limits = DeliveryLimits {
+            per_chat_interval: Duration::from_millis(10),
+            ..DeliveryLimits::default()
+        };
+        let start = Instant::now();
+        let (results, report) = run_test(&server, &limits, deliveries).await;
+
+        assert!(results.iter().all(DeliveryOutcome::is_sent));
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        // Resumed at the refused part, without sending the first one again
+        assert_eq!(texts_to(&server, 1).await, vec!["1/2", "2/2", "2/2"]);
+        assert_eq!(report.requeued, 1);
+        assert_eq!(report.sent, 2);
+        assert_eq!(report.messages, 3);
+        assert!(report.finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up_after_max_requeues() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"chat_id": "1"})))
+            .respond_with(flood_control(0))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(sent())
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"chat_id": "3"})))
+            .respond_with(ResponseTemplate::new(400).set_body_string(
+                r#"{"ok":false,"error_code":400,"description":"Bad Request: chat not found"}"#,
+            ))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let limits = DeliveryLimits {
+            max_requeues: 2,
+            ..DeliveryLimits::default()
+        };
+        let deliveries = vec![
+            delivery(1, &["a"]),
+            delivery(2, &["b"]),
+            delivery(3, &["c"]),
+        ];
+        let (results, report) = run_test(&server, &limits, deliveries).await;
+
+        let error = |outcome: &DeliveryOutcome| match outcome {
+            DeliveryOutcome::Failed { error, .. } => error.to_string(),
+            other => panic!("expected a failure, got {:?}", other),
+        };
+        assert!(error(&results[0]).contains("retry after 0"));
+        assert!(results[1].is_sent());
+        // Other errors aren't requeued
+        assert!(error(&results[2]).contains("chat not found"));
+        assert_eq!(texts_to(&server, 1).await.len(), 3);
+        assert_eq!(texts_to(&server, 3).await.len(), 1);
+        assert_eq!((report.sent, report.failed, report.requeued), (1, 2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_registers_fan_out() {
+        let server = MockServer::start().await;
+        let results = deliver(
+            &test_client(&server),
+            &DeliveryLimits::default(),
+            &NoLedger,
+            "test empty",
+            Vec::new(),
+        )
+        .await;
+        assert!(results.is_empty());
+
+        let report = FanOuts::global()
+            .report()
+            .into_iter()
+            .find(|r| r.name == "test empty")
+            .unwrap();
+        assert_eq!((report.total, report.pending), (0, 0));
+        assert!(report.finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_claims_each_chat_once_and_records_outcomes() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"chat_id": "3", "text": "2/2"}),
+            ))
+            .respond_with(ResponseTemplate::new(400).set_body_string(
+                r#"{"ok":false,"error_code":400,"description":"Bad Request: message is too long"}"#,
+            ))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"chat_id": "1"})))
+            .respond_with(flood_control(0))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(sent())
+            .mount(&server)
+            .await;
+
+        let ledger = TestLedger::default();
+        ledger.taken.lock().unwrap().push(2);
+        let limits = DeliveryLimits {
+            per_chat_interval: Duration::from_millis(10),
+            ..DeliveryLimits::default()
+        };
+        let deliveries = vec![
+            delivery(1, &["a"]),
+            delivery(2, &["b"]),
+            delivery(3, &["1/2", "2/2"]),
+        ];
+        let (outcomes, report) = run_with_ledger(&server, &limits, &ledger, deliveries).await;
+
+        assert!(outcomes[0].is_sent());
+        assert!(matches!(outcomes[1], DeliveryOutcome::Skipped));
+        assert!(matches!(
+            outcomes[2],
+            DeliveryOutcome::Failed { delivered: 1, .. }
+        ));
+        // Skipped chats get nothing; a requeued chat isn't claimed again
+        assert!(texts_to(&server, 2).await.is_empty());
+        let mut claims = ledger.claims.lock().unwrap().clone();
+        claims.sort();
+        assert_eq!(claims, vec![1, 2, 3]);
+        let mut records = ledger.records.lock().unwrap().clone();
+        records.sort();
+        assert_eq!(
+            records,
+            vec![(1, "sent 1".to_string()), (3, "failed after 1".to_string())]
+        );
+        assert_eq!((report.sent, report.skipped, report.failed), (1, 1, 1));
+        assert_eq!(report.pending, 0);
+    }
+
+    // ==================== Load Tests ====================
+
+    /// 300 chats against a mock Bot API that takes 20ms per call: the global
+    /// rate, not the number of workers or the API's latency, sets throughput
+    #[tokio::test]
+    async fn test_load_fan_out_throughput() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(sent().set_delay(Duration::from_millis(20)))
+            .mount(&server)
+            .await;
+
+        let chats = 300;
+        let limits = DeliveryLimits {
+            messages_per_second: 150,
+            concurrency: 16,
+            ..DeliveryLimits::default()
+        };
+        let deliveries = (0..chats).map(|i| delivery(i, &["digest"])).collect();
+        let start = Instant::now();
+        let (results, report) = run_test(&server, &limits, deliveries).await;
+        let elapsed = start.elapsed().as_secs_f64();
+
+        assert!(results.iter().all(DeliveryOutcome::is_sent));
+        assert_eq!(server.received_requests().await.unwrap().len(), 300);
+        let throughput = chats as f64 / elapsed;
+        // One at a time with the old 100ms pause: under 10 msg/s
+        assert!(
+            throughput > 100.0 && throughput <= 155.0,
+            "throughput {:.1} msg/s",
+            throughput
+        );
+        assert_eq!(report.sent, 300);
+        assert_eq!(report.pending, 0);
+        assert!(report.messages_per_second <= 155.0);
+    }
+}
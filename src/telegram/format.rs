@@ -0,0 +1,576 @@
+//! Parse modes for outgoing messages.
+//!
+//! Every message is built for one of Telegram's parse modes, MarkdownV2 (the
+//! default) or HTML, chosen with `TELEGRAM_PARSE_MODE`. [`ParseMode`] escapes
+//! literal text, wraps entities, renders model Markdown and i18n templates,
+//! and measures the result as Telegram does. The i18n strings are neutral
+//! text: `*bold*` spans and `{placeholder}`s, everything else literal.
+
+use super::escape_markdownv2_url;
+use super::length::{decode_entity, html_len, markdownv2_len};
+use super::markdown::render_markdown;
+
+/// Characters escaped in MarkdownV2 text (the 18 special characters plus `\`)
+pub(super) const SPECIAL_CHARS: [char; 19] = [
+    '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!', '\\',
+];
+
+/// Tags Telegram accepts in HTML parse mode
+const HTML_TAGS: [&str; 16] = [
+    "b",
+    "strong",
+    "i",
+    "em",
+    "u",
+    "ins",
+    "s",
+    "strike",
+    "del",
+    "span",
+    "tg-spoiler",
+    "a",
+    "tg-emoji",
+    "code",
+    "pre",
+    "blockquote",
+];
+
+/// Whitelisted tags whose attributes are kept (`href`, `class`, `emoji-id`)
+const HTML_TAGS_WITH_ATTRIBUTES: [&str; 5] = ["a", "span", "tg-emoji", "code", "pre"];
+
+/// How outgoing messages are formatted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Telegram MarkdownV2: literal text escaped with backslashes
+    #[default]
+    MarkdownV2,
+    /// Telegram HTML: literal text escaped as entities
+    Html,
+}
+
+impl ParseMode {
+    pub const ALL: [ParseMode; 2] = [Self::MarkdownV2, Self::Html];
+
+    /// Parse "MarkdownV2" or "HTML" (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// The Bot API `parse_mode` value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MarkdownV2 => "MarkdownV2",
+            Self::Html => "HTML",
+        }
+    }
+
+    /// Escape literal text
+    pub fn escape(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            self.push_escaped(&mut out, c);
+        }
+        out
+    }
+
+    /// Append one literal character, escaped
+    pub(super) fn push_escaped(&self, out: &mut String, c: char) {
+        match self {
+            Self::MarkdownV2 => {
+                if SPECIAL_CHARS.contains(&c) {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+            Self::Html => match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                _ => out.push(c),
+            },
+        }
+    }
+
+    /// Bold around text that is already formatted for this mode
+    pub fn bold(&self, text: &str) -> String {
+        match self {
+            Self::MarkdownV2 => format!("*{}*", text),
+            Self::Html => format!("<b>{}</b>", text),
+        }
+    }
+
+    /// Italic around text that is already formatted for this mode
+    pub fn italic(&self, text: &str) -> String {
+        match self {
+            Self::MarkdownV2 => format!("_{}_", text),
+            Self::Html => format!("<i>{}</i>", text),
+        }
+    }
+
+    /// Inline code showing `text` literally
+    pub fn code(&self, text: &str) -> String {
+        match self {
+            Self::MarkdownV2 => {
+                let mut out = String::with_capacity(text.len() + 2);
+                out.push('`');
+                for c in text.chars() {
+                    if c == '`' || c == '\\' {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push('`');
+                out
+            }
+            Self::Html => format!("<code>{}</code>", escape_html(text)),
+        }
+    }
+
+    /// A preformatted block showing `text` literally
+    pub fn pre(&self, text: &str) -> String {
+        match self {
+            Self::MarkdownV2 => {
+                let code = self.code(text);
+                format!("```\n{}\n```", &code[1..code.len() - 1])
+            }
+            Self::Html => format!("<pre>{}</pre>", escape_html(text)),
+        }
+    }
+
+    /// A link with an already formatted `label` to a literal `url`
+    pub fn link(&self, label: &str, url: &str) -> String {
+        match self {
+            Self::MarkdownV2 => format!("[{}]({})", label, escape_markdownv2_url(url)),
+            Self::Html => format!("<a href=\"{}\">{}</a>", escape_html(url), label),
+        }
+    }
+
+    /// Render model Markdown with its formatting kept (see [`render_markdown`])
+    pub fn render(&self, markdown: &str) -> String {
+        render_markdown(markdown, *self)
+    }
+
+    /// Visible length in UTF-16 units, as Telegram counts it
+    pub fn visible_len(&self, text: &str) -> usize {
+        match self {
+            Self::MarkdownV2 => markdownv2_len(text),
+            Self::Html => html_len(text),
+        }
+    }
+
+    /// Render a neutral i18n template: `*bold*` spans become bold, each
+    /// `{name}` is replaced by its value from `args`, and everything else is
+    /// escaped. Values are inserted as given, so plain text must go through
+    /// [`ParseMode::escape`] first. An unmatched `*` or unknown placeholder
+    /// is shown literally.
+    pub fn template(&self, template: &str, args: &[(&str, &str)]) -> String {
+        let segments: Vec<&str> = template.split('*').collect();
+        let balanced = segments.len() % 2 == 1;
+        let mut out = String::with_capacity(template.len() * 2);
+
+        for (i, segment) in segments.iter().enumerate() {
+            let filled = self.fill(segment, args);
+            if i % 2 == 0 {
+                out.push_str(&filled);
+            } else if balanced {
+                out.push_str(&self.bold(&filled));
+            } else {
+                self.push_escaped(&mut out, '*');
+                out.push_str(&filled);
+            }
+        }
+        out
+    }
+
+    /// Escape `text`, replacing `{name}` placeholders from `args`
+    fn fill(&self, text: &str, args: &[(&str, &str)]) -> String {
+        let mut out = String::with_capacity(text.len() * 2);
+        let mut rest = text;
+
+        while let Some(c) = rest.chars().next() {
+            if c == '{' {
+                let value = args.iter().find(|(name, _)| {
+                    rest[1..].starts_with(name) && rest[1 + name.len()..].starts_with('}')
+                });
+                if let Some((name, value)) = value {
+                    out.push_str(value);
+                    rest = &rest[name.len() + 2..];
+                    continue;
+                }
+            }
+            self.push_escaped(&mut out, c);
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+}
+
+/// Escape text for Telegram HTML parse mode (also safe inside quoted attributes)
+pub fn escape_html(text: &str) -> String {
+    ParseMode::Html.escape(text)
+}
+
+/// Make caller-written HTML safe to send: tags Telegram supports are kept
+/// when they are properly closed and nested, and any other tag, stray `<`,
+/// `>` or `&` is escaped so it shows as written.
+pub fn sanitize_html(html: &str) -> String {
+    let tokens = tokenize_html(html);
+
+    // Pair opening and closing tags; whatever is left unpaired is escaped
+    let mut keep = vec![false; tokens.len()];
+    let mut open: Vec<(usize, &str)> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            HtmlToken::Open { name, .. } => open.push((i, name)),
+            HtmlToken::Close { name, .. } => {
+                if let Some((start, _)) = open.pop_if(|(_, top)| top == name) {
+                    keep[start] = true;
+                    keep[i] = true;
+                }
+            }
+            HtmlToken::Text(_) => {}
+        }
+    }
+
+    let mut out = String::with_capacity(html.len());
+    for (token, keep) in tokens.iter().zip(keep) {
+        match token {
+            HtmlToken::Text(text) => out.push_str(&escape_html_text(text)),
+            HtmlToken::Open { name, raw } if keep => {
+                if HTML_TAGS_WITH_ATTRIBUTES.contains(name) {
+                    out.push_str(raw);
+                } else {
+                    out.push_str(&format!("<{}>", name));
+                }
+            }
+            HtmlToken::Close { name, .. } if keep => out.push_str(&format!("</{}>", name)),
+            HtmlToken::Open { raw, .. } | HtmlToken::Close { raw, .. } => {
+                out.push_str(&escape_html(raw))
+            }
+        }
+    }
+    out
+}
+
+/// A piece of caller-written HTML: text, or a whitelisted opening or closing tag
+enum HtmlToken<'a> {
+    Text(&'a str),
+    Open { name: &'a str, raw: &'a str },
+    Close { name: &'a str, raw: &'a str },
+}
+
+fn tokenize_html(html: &str) -> Vec<HtmlToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while let Some(offset) = html[i..].find('<') {
+        let start = i + offset;
+        let tag = html[start..]
+            .find('>')
+            .map(|end| &html[start..=start + end])
+            .and_then(|raw| parse_tag(raw).map(|token| (raw, token)));
+        match tag {
+            Some((raw, token)) => {
+                if text_start < start {
+                    tokens.push(HtmlToken::Text(&html[text_start..start]));
+                }
+                tokens.push(token);
+                i = start + raw.len();
+                text_start = i;
+            }
+            None => i = start + 1,
+        }
+    }
+    if text_start < html.len() {
+        tokens.push(HtmlToken::Text(&html[text_start..]));
+    }
+    tokens
+}
+
+/// A whitelisted tag ("<b>", "</b>", "<a href=\"...\">"), or None
+fn parse_tag(raw: &str) -> Option<HtmlToken<'_>> {
+    let inner = &raw[1..raw.len() - 1];
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let name_len = inner
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(inner.len());
+    let name = &inner[..name_len];
+    let rest = &inner[name_len..];
+
+    let name = *HTML_TAGS
+        .iter()
+        .find(|tag| tag.eq_ignore_ascii_case(name))?;
+    if closing {
+        return rest
+            .trim()
+            .is_empty()
+            .then_some(HtmlToken::Close { name, raw });
+    }
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(HtmlToken::Open { name, raw })
+}
+
+/// Escape text between tags, keeping entities that are already valid
+fn escape_html_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '&' {
+            if let Some(end) = rest.find(';') {
+                if decode_entity(&rest[1..end]).is_some() {
+                    out.push_str(&rest[..=end]);
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+        }
+        ParseMode::Html.push_escaped(&mut out, c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::length::utf16_len;
+    use proptest::prelude::*;
+
+    // ==================== Parse Mode Tests ====================
+
+    #[test]
+    fn test_parse_mode_parse() {
+        assert_eq!(ParseMode::parse("MarkdownV2"), Some(ParseMode::MarkdownV2));
+        assert_eq!(ParseMode::parse(" html "), Some(ParseMode::Html));
+        assert_eq!(ParseMode::parse("markdown"), None);
+        assert_eq!(ParseMode::default(), ParseMode::MarkdownV2);
+        for mode in ParseMode::ALL {
+            assert_eq!(ParseMode::parse(mode.as_str()), Some(mode));
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            ParseMode::MarkdownV2.escape("1.0 - done! (C:\\)"),
+            "1\\.0 \\- done\\! \\(C:\\\\\\)"
+        );
+        assert_eq!(
+            ParseMode::Html.escape("a < b && \"c\" > d"),
+            "a &lt; b &amp;&amp; &quot;c&quot; &gt; d"
+        );
+    }
+
+    #[test]
+    fn test_entities() {
+        let md = ParseMode::MarkdownV2;
+        assert_eq!(md.bold("x"), "*x*");
+        assert_eq!(md.italic("x"), "_x_");
+        assert_eq!(md.code("a`b\\c"), "`a\\`b\\\\c`");
+        assert_eq!(
+            md.link("post", "https://x.com/(a)"),
+            "[post](https://x.com/(a\\))"
+        );
+
+        let html = ParseMode::Html;
+        assert_eq!(html.bold("x"), "<b>x</b>");
+        assert_eq!(html.italic("x"), "<i>x</i>");
+        assert_eq!(html.code("a<b"), "<code>a&lt;b</code>");
+        assert_eq!(
+            html.link("post", "https://x.com/?a=1&b=\"2\""),
+            "<a href=\"https://x.com/?a=1&amp;b=&quot;2&quot;\">post</a>"
+        );
+    }
+
+    // ==================== Template Tests ====================
+
+    #[test]
+    fn test_template_escapes_for_each_mode() {
+        let template = "✅ Subscribed to *{name}*. Use /long split - (1/2)!";
+        let args = [("name", "Rust & Go")];
+
+        let md_args = [("name", ParseMode::MarkdownV2.escape(args[0].1))];
+        let md_args: Vec<(&str, &str)> = md_args.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        assert_eq!(
+            ParseMode::MarkdownV2.template(template, &md_args),
+            "✅ Subscribed to *Rust & Go*\\. Use /long split \\- \\(1/2\\)\\!"
+        );
+
+        let html_value = ParseMode::Html.escape(args[0].1);
+        assert_eq!(
+            ParseMode::Html.template(template, &[("name", &html_value)]),
+            "✅ Subscribed to <b>Rust &amp; Go</b>. Use /long split - (1/2)!"
+        );
+    }
+
+    #[test]
+    fn test_template_unknown_placeholder_and_unmatched_marker_are_literal() {
+        assert_eq!(
+            ParseMode::MarkdownV2.template("{other} 2*3", &[("name", "x")]),
+            "\\{other\\} 2\\*3"
+        );
+        assert_eq!(
+            ParseMode::Html.template("{other} 2*3 <b>", &[]),
+            "{other} 2*3 &lt;b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_template_values_are_inserted_as_given() {
+        let current = ParseMode::Html.code("split");
+        assert_eq!(
+            ParseMode::Html.template("Current: {current}", &[("current", &current)]),
+            "Current: <code>split</code>"
+        );
+    }
+
+    // ==================== HTML Whitelist Tests ====================
+
+    #[test]
+    fn test_sanitize_keeps_supported_tags() {
+        let html = "<b>bold</b> <i>it</i> <a href=\"https://x.com\">link</a> \
+                    <span class=\"tg-spoiler\">s</span> <pre><code class=\"language-rust\">x</code></pre>";
+        assert_eq!(sanitize_html(html), html);
+        assert_eq!(sanitize_html("<B>x</B>"), "<b>x</b>");
+        assert_eq!(sanitize_html("<b onclick=\"x()\">x</b>"), "<b>x</b>");
+    }
+
+    #[test]
+    fn test_sanitize_escapes_unsupported_and_unbalanced_tags() {
+        assert_eq!(
+            sanitize_html("<script>alert(1)</script>"),
+            "&lt;script&gt;alert(1)&lt;/script&gt;"
+        );
+        assert_eq!(sanitize_html("<b>open"), "&lt;b&gt;open");
+        assert_eq!(sanitize_html("close</i>"), "close&lt;/i&gt;");
+        assert_eq!(
+            sanitize_html("<b><i>x</b></i>"),
+            "&lt;b&gt;<i>x&lt;/b&gt;</i>"
+        );
+        assert_eq!(
+            sanitize_html("<bold>x</bold>"),
+            "&lt;bold&gt;x&lt;/bold&gt;"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_escapes_stray_characters() {
+        assert_eq!(sanitize_html("1 < 2 > 0"), "1 &lt; 2 &gt; 0");
+        assert_eq!(sanitize_html("R&D &amp; more"), "R&amp;D &amp; more");
+        assert_eq!(
+            sanitize_html("&#128640; &lt;tag&gt;"),
+            "&#128640; &lt;tag&gt;"
+        );
+    }
+
+    // ==================== Property Tests ====================
+
+    /// Text with every character either mode treats specially
+    fn literal_text() -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                "[a-zA-Zñ日🚀 ]{1,6}",
+                prop::sample::select(vec![
+                    "_", "*", "[", "]", "(", ")", "~", "`", ">", "#", "+", "-", "=", "|", "{", "}",
+                    ".", "!", "\\", "<", "&", "\"", "\n",
+                ])
+                .prop_map(String::from),
+            ],
+            0..30,
+        )
+        .prop_map(|parts| parts.concat())
+    }
+
+    /// HTML-like input: text and a mix of supported, unsupported and broken tags
+    fn html_text() -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                literal_text(),
+                prop::sample::select(vec![
+                    "<b>",
+                    "</b>",
+                    "<i>",
+                    "</i>",
+                    "<a href=\"https://x.com\">",
+                    "</a>",
+                    "<code>",
+                    "</code>",
+                    "<div>",
+                    "</div>",
+                    "<b",
+                    "&amp;",
+                    "&bogus;",
+                ])
+                .prop_map(String::from),
+            ],
+            0..20,
+        )
+        .prop_map(|parts| parts.concat())
+    }
+
+    /// Whether every tag in `html` is whitelisted and properly nested
+    fn tags_are_balanced(html: &str) -> bool {
+        let mut open: Vec<String> = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            let Some(end) = rest[start..].find('>') else {
+                return false;
+            };
+            let tag = &rest[start + 1..start + end];
+            match tag.strip_prefix('/') {
+                Some(name) => {
+                    if open.pop().as_deref() != Some(name) {
+                        return false;
+                    }
+                }
+                None => {
+                    let name = tag.split(' ').next().unwrap_or_default();
+                    if !HTML_TAGS.contains(&name) {
+                        return false;
+                    }
+                    open.push(name.to_string());
+                }
+            }
+            rest = &rest[start + end + 1..];
+        }
+        open.is_empty()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_escaped_text_has_its_plain_length(text in literal_text()) {
+            for mode in ParseMode::ALL {
+                prop_assert_eq!(mode.visible_len(&mode.escape(&text)), utf16_len(&text));
+            }
+        }
+
+        #[test]
+        fn prop_template_without_markers_is_escaped_text(text in "[a-zA-Z0-9 .,!?()<>&-]{0,40}") {
+            for mode in ParseMode::ALL {
+                prop_assert_eq!(mode.template(&text, &[]), mode.escape(&text));
+            }
+        }
+
+        #[test]
+        fn prop_sanitized_html_has_balanced_whitelisted_tags(html in html_text()) {
+            let sanitized = sanitize_html(&html);
+            prop_assert!(tags_are_balanced(&sanitized), "{:?} -> {:?}", html, sanitized);
+        }
+
+        #[test]
+        fn prop_sanitize_keeps_escaped_text(text in literal_text()) {
+            let escaped = escape_html(&text);
+            prop_assert_eq!(sanitize_html(&escaped), escaped);
+        }
+    }
+}
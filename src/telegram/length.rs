@@ -0,0 +1,285 @@
+//! Message length as Telegram counts it.
+//!
+//! The Bot API limits a message to 4096 characters *after entities parsing*,
+//! counted in UTF-16 code units: formatting markers, escape backslashes, link
+//! URLs and HTML tags don't count, while an emoji outside the Basic
+//! Multilingual Plane counts twice. `String::len()` (UTF-8 bytes) overcounts
+//! accented text and markup, so length decisions use these functions instead.
+
+/// Maximum length of a message's text after entities parsing (UTF-16 units)
+pub const MESSAGE_LIMIT: usize = 4096;
+
+/// UTF-16 length of plain text
+pub fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Visible length of MarkdownV2 text in UTF-16 units: escapes count as the
+/// escaped character, entity markers (`*`, `_`, `~`, `||`, `` ` ``, `>` at
+/// the start of a line), link brackets and link URLs don't count.
+pub fn markdownv2_len(text: &str) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut len = 0;
+    let mut i = 0;
+    let mut in_code = false;
+    let mut in_pre = false;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let at_line_start = i == 0 || chars[i - 1] == '\n';
+
+        if c == '\\' && i + 1 < chars.len() {
+            len += chars[i + 1].len_utf16();
+            i += 2;
+            continue;
+        }
+
+        if chars[i..].starts_with(&['`', '`', '`']) && !in_code {
+            i += 3;
+            if !in_pre {
+                // The language of a pre block ("```rust\n") is not shown
+                if let Some(newline) = chars[i..].iter().position(|&c| c == '\n') {
+                    if !chars[i..i + newline].iter().any(|c| c.is_whitespace()) {
+                        i += newline + 1;
+                    }
+                }
+            }
+            in_pre = !in_pre;
+            continue;
+        }
+        if c == '`' && !in_pre {
+            in_code = !in_code;
+            i += 1;
+            continue;
+        }
+        if in_code || in_pre {
+            len += c.len_utf16();
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '*' | '_' | '~' | '[' => i += 1,
+            '|' if chars.get(i + 1) == Some(&'|') => i += 2,
+            '!' if chars.get(i + 1) == Some(&'[') => i += 1,
+            '>' if at_line_start => i += 1,
+            ']' if chars.get(i + 1) == Some(&'(') => {
+                // Skip the URL; inside it only ')' and '\' are escaped
+                i += 2;
+                while i < chars.len() && chars[i] != ')' {
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            ']' => i += 1,
+            _ => {
+                len += c.len_utf16();
+                i += 1;
+            }
+        }
+    }
+    len
+}
+
+/// Visible length of HTML text in UTF-16 units: tags don't count, and an
+/// entity (`&amp;`, `&#128640;`) counts as the character it stands for.
+pub fn html_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            match rest.find('>') {
+                Some(end) => {
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+                None => {
+                    len += 1;
+                    rest = &rest[1..];
+                    continue;
+                }
+            }
+        }
+        if c == '&' {
+            if let Some(decoded) = rest
+                .find(';')
+                .and_then(|end| decode_entity(&rest[1..end]).map(|decoded| (decoded, end)))
+            {
+                len += decoded.0.len_utf16();
+                rest = &rest[decoded.1 + 1..];
+                continue;
+            }
+        }
+        len += c.len_utf16();
+        rest = &rest[c.len_utf8()..];
+    }
+    len
+}
+
+/// The character of an HTML entity name without `&` and `;`
+pub(super) fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::escape_markdownv2;
+    use super::*;
+    use proptest::prelude::*;
+
+    // ==================== UTF-16 Tests ====================
+
+    #[test]
+    fn test_utf16_len() {
+        assert_eq!(utf16_len("hello"), 5);
+        // Accented letters: 2 bytes in UTF-8, 1 unit in UTF-16
+        assert_eq!(utf16_len("año"), 3);
+        assert_eq!("año".len(), 4);
+        // Emoji outside the BMP: 4 bytes, 2 units
+        assert_eq!(utf16_len("🚀"), 2);
+        // Emoji with a variation selector
+        assert_eq!(utf16_len("⚖️"), 2);
+        // CJK: 3 bytes, 1 unit
+        assert_eq!(utf16_len("日本"), 2);
+    }
+
+    // ==================== MarkdownV2 Tests ====================
+
+    #[test]
+    fn test_markdownv2_escapes_count_once() {
+        assert_eq!(markdownv2_len("1\\.0 \\- done\\!"), "1.0 - done!".len());
+    }
+
+    #[test]
+    fn test_markdownv2_markers_do_not_count() {
+        assert_eq!(markdownv2_len("*bold* _italic_ __under__ ~strike~"), 24);
+        assert_eq!(markdownv2_len("||spoiler||"), 7);
+        assert_eq!(markdownv2_len("`code`"), 4);
+        assert_eq!(markdownv2_len(">quote\n>more"), 10);
+    }
+
+    #[test]
+    fn test_markdownv2_link_url_does_not_count() {
+        assert_eq!(
+            markdownv2_len("[source post](https://x.com/a/status/1_\\)x)"),
+            "source post".len()
+        );
+        assert_eq!(
+            markdownv2_len("![👍](tg://emoji?id=5368324170671202286)"),
+            2
+        );
+    }
+
+    #[test]
+    fn test_markdownv2_code_keeps_markers() {
+        assert_eq!(markdownv2_len("`a*b_c`"), 5);
+        assert_eq!(
+            markdownv2_len("```rust\nlet x = *y;```"),
+            "let x = *y;".len()
+        );
+    }
+
+    #[test]
+    fn test_markdownv2_digest_header() {
+        let header = "📰 *Twitter Summary*\n_2026\\-10\\-18 08:00 UTC_\n\n";
+        let visible = "📰 Twitter Summary\n2026-10-18 08:00 UTC\n\n";
+        assert_eq!(markdownv2_len(header), utf16_len(visible));
+        assert!(markdownv2_len(header) < header.len());
+    }
+
+    // ==================== HTML Tests ====================
+
+    #[test]
+    fn test_html_tags_and_entities() {
+        assert_eq!(
+            html_len("<b>bold</b> &amp; <i>more</i>"),
+            "bold & more".len()
+        );
+        assert_eq!(
+            html_len("<a href=\"https://x.com/a\">source</a>"),
+            "source".len()
+        );
+        assert_eq!(html_len("&lt;tag&gt; &quot;q&quot;"), "<tag> \"q\"".len());
+        assert_eq!(html_len("&#128640; &#x1F680;"), 5);
+        // Unknown entities and stray characters count as written
+        assert_eq!(html_len("a &nbsp b < c"), 13);
+    }
+
+    // ==================== Property Tests ====================
+
+    /// Mixed-script text: Latin, accented, CJK, Cyrillic, emoji and
+    /// MarkdownV2/HTML special characters
+    fn mixed_text() -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                "[a-zA-Z0-9 ]{1,8}",
+                "[áéíóúñü¿¡]{1,4}",
+                "[日本語中文한국어]{1,4}",
+                "[привет]{1,4}",
+                prop::sample::select(vec!["🚀", "🧠", "⚖️", "👩‍💻", "🇵🇪"]).prop_map(String::from),
+                prop::sample::select(vec![
+                    "_", "*", "[", "]", "(", ")", "~", "`", ">", "#", "+", "-", "=", "|", "{", "}",
+                    ".", "!", "<", "&", "\n",
+                ])
+                .prop_map(String::from),
+            ],
+            0..40,
+        )
+        .prop_map(|parts| parts.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_escaped_text_has_its_plain_length(text in mixed_text()) {
+            let escaped = super::super::escape_markdownv2_simple(&text);
+            prop_assert_eq!(markdownv2_len(&escaped), utf16_len(&text));
+        }
+
+        #[test]
+        fn prop_bold_and_links_add_no_length(text in mixed_text(), label in "[a-z ]{1,10}") {
+            let escaped = super::super::escape_markdownv2_simple(&text);
+            let formatted = format!("*{}* [{}](https://x.com/a/status/1)", escaped, label);
+            prop_assert_eq!(
+                markdownv2_len(&formatted),
+                utf16_len(&text) + 1 + label.encode_utf16().count()
+            );
+        }
+
+        #[test]
+        fn prop_visible_length_never_exceeds_utf16_length(text in mixed_text()) {
+            let escaped = escape_markdownv2(&text);
+            prop_assert!(markdownv2_len(&escaped) <= utf16_len(&escaped));
+            prop_assert!(html_len(&text) <= utf16_len(&text));
+        }
+
+        #[test]
+        fn prop_html_escaped_text_has_its_plain_length(text in mixed_text()) {
+            let escaped = text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+            prop_assert_eq!(html_len(&escaped), utf16_len(&text));
+        }
+
+        #[test]
+        fn prop_utf16_len_matches_char_widths(text in mixed_text()) {
+            let expected: usize = text.chars().map(char::len_utf16).sum();
+            prop_assert_eq!(utf16_len(&text), expected);
+            prop_assert!(utf16_len(&text) <= text.len());
+        }
+    }
+}
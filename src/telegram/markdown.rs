@@ -0,0 +1,422 @@
+//! Model Markdown to Telegram MarkdownV2 or HTML, keeping the formatting.
+//!
+//! Summaries come from the model as light Markdown: `*bold*` (or `**bold**`),
+//! `_italic_`, `` `code` ``, `[label](url)` links and `-` bullets.
+//! [`render_markdown`] turns those into entities of the target parse mode and
+//! escapes everything else, unlike [`escape_markdownv2`](super::escape_markdownv2),
+//! which only keeps links. Formatting never spans lines, and a marker without
+//! a matching partner (`*bold`, `snake_case`, `2*3*4`) is shown literally, so
+//! any input gives a valid message.
+
+use super::format::ParseMode;
+
+/// Render model Markdown as MarkdownV2 with bold, italic, code and links kept
+pub fn render_markdownv2(markdown: &str) -> String {
+    render_markdown(markdown, ParseMode::MarkdownV2)
+}
+
+/// Render model Markdown for `mode` with bold, italic, code and links kept
+pub fn render_markdown(markdown: &str, mode: ParseMode) -> String {
+    markdown
+        .split('\n')
+        .map(|line| render_line(line, mode))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A line: a leading bullet or heading marker, then inline text
+fn render_line(line: &str, mode: ParseMode) -> String {
+    let content = line.trim_start();
+    let indent = &line[..line.len() - content.len()];
+
+    for bullet in ["- ", "* ", "+ ", "• "] {
+        if let Some(rest) = content.strip_prefix(bullet) {
+            return format!("{}• {}", indent, render_inline(rest, Inline::NONE, mode));
+        }
+    }
+
+    let heading = content.trim_start_matches('#');
+    if heading.len() < content.len() && heading.starts_with(' ') && !heading.trim().is_empty() {
+        return format!(
+            "{}{}",
+            indent,
+            mode.bold(&render_inline(heading.trim(), Inline::BOLD, mode))
+        );
+    }
+
+    format!("{}{}", indent, render_inline(content, Inline::NONE, mode))
+}
+
+/// Entities already open around the text being rendered (not opened again)
+#[derive(Clone, Copy)]
+struct Inline {
+    bold: bool,
+    italic: bool,
+    link: bool,
+}
+
+impl Inline {
+    const NONE: Inline = Inline {
+        bold: false,
+        italic: false,
+        link: false,
+    };
+    const BOLD: Inline = Inline {
+        bold: true,
+        italic: false,
+        link: false,
+    };
+}
+
+fn render_inline(text: &str, open: Inline, mode: ParseMode) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        // `code`
+        if c == '`' {
+            if let Some(end) = find_char(&chars, i + 1, '`') {
+                if end > i + 1 {
+                    let code: String = chars[i + 1..end].iter().collect();
+                    out.push_str(&mode.code(&code));
+                    i = end + 1;
+                    continue;
+                }
+            }
+        }
+
+        // [label](url)
+        if c == '[' && !open.link {
+            if let Some((label_end, url_end)) = find_link(&chars, i) {
+                let label: String = chars[i + 1..label_end].iter().collect();
+                let url: String = chars[label_end + 2..url_end].iter().collect();
+                let label = render_inline(&label, Inline { link: true, ..open }, mode);
+                out.push_str(&mode.link(&label, &url));
+                i = url_end + 1;
+                continue;
+            }
+        }
+
+        // **bold** (CommonMark) and *bold* (Telegram style)
+        if c == '*' && !open.bold {
+            let width = if chars.get(i + 1) == Some(&'*') { 2 } else { 1 };
+            if let Some(end) = find_closing(&chars, i, width, '*') {
+                let inner: String = chars[i + width..end].iter().collect();
+                let inner = render_inline(&inner, Inline { bold: true, ..open }, mode);
+                out.push_str(&mode.bold(&inner));
+                i = end + width;
+                continue;
+            }
+        }
+
+        // _italic_
+        if c == '_' && !open.italic {
+            if let Some(end) = find_closing(&chars, i, 1, '_') {
+                let inner: String = chars[i + 1..end].iter().collect();
+                let inner = render_inline(
+                    &inner,
+                    Inline {
+                        italic: true,
+                        ..open
+                    },
+                    mode,
+                );
+                out.push_str(&mode.italic(&inner));
+                i = end + 1;
+                continue;
+            }
+        }
+
+        mode.push_escaped(&mut out, c);
+        i += 1;
+    }
+    out
+}
+
+fn find_char(chars: &[char], from: usize, target: char) -> Option<usize> {
+    chars[from..]
+        .iter()
+        .position(|&c| c == target)
+        .map(|p| from + p)
+}
+
+/// For `[` at `start`, the positions of the `]` and of the `)` closing the URL
+fn find_link(chars: &[char], start: usize) -> Option<(usize, usize)> {
+    let label_end = find_char(chars, start + 1, ']')?;
+    if label_end == start + 1 || chars.get(label_end + 1) != Some(&'(') {
+        return None;
+    }
+    if chars[start + 1..label_end].contains(&'[') {
+        return None;
+    }
+    let url_end = find_char(chars, label_end + 2, ')')?;
+    let url = &chars[label_end + 2..url_end];
+    (!url.is_empty() && !url.iter().any(|c| c.is_whitespace())).then_some((label_end, url_end))
+}
+
+/// The position of the marker closing the one at `start` (`width` copies of
+/// `marker`). Like Markdown's flanking rules, an opening marker is followed by
+/// a non-space and not preceded by a word character, and a closing marker is
+/// preceded by a non-space and not followed by a word character. Markers next
+/// to `_` are left alone so that `__` (underline in MarkdownV2) never appears.
+fn find_closing(chars: &[char], start: usize, width: usize, marker: char) -> Option<usize> {
+    let is_word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric() || *c == '_');
+    let is_marker = |i: usize| (0..width).all(|k| chars.get(i + k) == Some(&marker));
+
+    let before = start.checked_sub(1).and_then(|p| chars.get(p));
+    let first = chars.get(start + width);
+    if is_word(before) || first.is_none_or(|c| c.is_whitespace() || *c == marker) {
+        return None;
+    }
+
+    let mut i = start + width + 1;
+    while i + width <= chars.len() {
+        if chars[i] == '`' {
+            // Markers inside code don't close
+            i = find_char(chars, i + 1, '`').map_or(i + 1, |end| end + 1);
+            continue;
+        }
+        if is_marker(i)
+            && !chars[i - 1].is_whitespace()
+            && chars[i - 1] != marker
+            && !is_word(chars.get(i + width))
+            && chars.get(i + width) != Some(&marker)
+        {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::format::SPECIAL_CHARS;
+    use crate::telegram::length::{markdownv2_len, utf16_len};
+    use proptest::prelude::*;
+
+    /// Check `text` against Telegram's MarkdownV2 rules: special characters
+    /// outside entities are escaped, entities are closed and properly nested,
+    /// and inside code and link URLs only the allowed characters appear bare.
+    fn validate_markdownv2(text: &str) -> Result<(), String> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut open: Vec<char> = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '\\' => {
+                    if i + 1 >= chars.len() {
+                        return Err("trailing backslash".to_string());
+                    }
+                    i += 2;
+                    continue;
+                }
+                '`' => {
+                    let mut j = i + 1;
+                    loop {
+                        match chars.get(j) {
+                            None => return Err(format!("unclosed code at {}", i)),
+                            Some('`') => break,
+                            Some('\\') => {
+                                if !matches!(chars.get(j + 1), Some('`') | Some('\\')) {
+                                    return Err(format!("bad escape in code at {}", j));
+                                }
+                                j += 2;
+                            }
+                            Some(_) => j += 1,
+                        }
+                    }
+                    i = j + 1;
+                    continue;
+                }
+                '*' | '_' | '~' | '[' => {
+                    if c == '[' && open.contains(&'[') {
+                        return Err(format!("nested link at {}", i));
+                    }
+                    if c != '[' && open.last() == Some(&c) {
+                        open.pop();
+                    } else if open.contains(&c) {
+                        return Err(format!("overlapping '{}' at {}", c, i));
+                    } else {
+                        if c == '_' && chars.get(i + 1) == Some(&'_') {
+                            return Err(format!("'__' (underline) at {}", i));
+                        }
+                        open.push(c);
+                    }
+                }
+                ']' => {
+                    if open.last() != Some(&'[') || chars.get(i + 1) != Some(&'(') {
+                        return Err(format!("bad link end at {}", i));
+                    }
+                    open.pop();
+                    let mut j = i + 2;
+                    loop {
+                        match chars.get(j) {
+                            None => return Err(format!("unclosed url at {}", i)),
+                            Some(')') => break,
+                            Some('\\') => j += 2,
+                            Some(_) => j += 1,
+                        }
+                    }
+                    i = j + 1;
+                    continue;
+                }
+                _ if SPECIAL_CHARS.contains(&c) => {
+                    return Err(format!("unescaped '{}' at {}", c, i));
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        if open.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("unclosed entities: {:?}", open))
+        }
+    }
+
+    // ==================== Formatting Tests ====================
+
+    #[test]
+    fn test_bold_italic_and_code_are_kept() {
+        assert_eq!(
+            render_markdownv2("*Big news* and _small print_ in `main.rs`."),
+            "*Big news* and _small print_ in `main.rs`\\."
+        );
+        assert_eq!(render_markdownv2("**Big news**!"), "*Big news*\\!");
+    }
+
+    #[test]
+    fn test_links_keep_label_formatting() {
+        assert_eq!(
+            render_markdownv2("See [the *v1.0* post](https://x.com/a_b/status/1)."),
+            "See [the *v1\\.0* post](https://x.com/a_b/status/1)\\."
+        );
+    }
+
+    #[test]
+    fn test_nested_bold_and_italic() {
+        assert_eq!(
+            render_markdownv2("*Rust _2.0_ ships*"),
+            "*Rust _2\\.0_ ships*"
+        );
+    }
+
+    #[test]
+    fn test_bullets_and_headings() {
+        let markdown =
+            "## Top takeaways\n- *Acme* ships Widget 2 — big deal.\n* second\n  - nested";
+        assert_eq!(
+            render_markdownv2(markdown),
+            "*Top takeaways*\n• *Acme* ships Widget 2 — big deal\\.\n• second\n  • nested"
+        );
+    }
+
+    #[test]
+    fn test_digest_bullet() {
+        let markdown =
+            "- *Foo 1.0 is out* — faster builds. [release notes](https://x.com/foo/status/3)";
+        assert_eq!(
+            render_markdownv2(markdown),
+            "• *Foo 1\\.0 is out* — faster builds\\. [release notes](https://x.com/foo/status/3)"
+        );
+    }
+
+    // ==================== Unbalanced Marker Tests ====================
+
+    #[test]
+    fn test_unbalanced_markers_are_literal() {
+        assert_eq!(render_markdownv2("*bold"), "\\*bold");
+        assert_eq!(render_markdownv2("a `code"), "a \\`code");
+        assert_eq!(
+            render_markdownv2("[label](no close"),
+            "\\[label\\]\\(no close"
+        );
+        assert_eq!(render_markdownv2("**"), "\\*\\*");
+        assert_eq!(render_markdownv2("* not a bullet*"), "• not a bullet\\*");
+    }
+
+    #[test]
+    fn test_intraword_markers_are_literal() {
+        assert_eq!(render_markdownv2("snake_case_name"), "snake\\_case\\_name");
+        assert_eq!(render_markdownv2("2*3*4 = 24"), "2\\*3\\*4 \\= 24");
+        assert_eq!(render_markdownv2("__init__"), "\\_\\_init\\_\\_");
+    }
+
+    #[test]
+    fn test_formatting_does_not_span_lines() {
+        assert_eq!(render_markdownv2("*one\ntwo*"), "\\*one\ntwo\\*");
+    }
+
+    #[test]
+    fn test_backslash_is_escaped() {
+        assert_eq!(render_markdownv2("C:\\path"), "C:\\\\path");
+    }
+
+    // ==================== Property Tests ====================
+
+    /// Model-like text: words, spaces, newlines and every special character
+    fn markdown_text() -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                "[a-zA-Z0-9]{1,6}",
+                "[áñü日本🚀 ]{1,3}",
+                Just(" ".to_string()),
+                Just("\n".to_string()),
+                Just("- ".to_string()),
+                Just("**".to_string()),
+                prop::sample::select(SPECIAL_CHARS.to_vec()).prop_map(String::from),
+            ],
+            0..40,
+        )
+        .prop_map(|parts| parts.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_output_is_valid_markdownv2(text in markdown_text()) {
+            let rendered = render_markdownv2(&text);
+            prop_assert!(
+                validate_markdownv2(&rendered).is_ok(),
+                "{:?} -> {:?}: {:?}",
+                text,
+                rendered,
+                validate_markdownv2(&rendered)
+            );
+        }
+
+        #[test]
+        fn prop_text_without_markers_keeps_its_length(
+            text in "[a-zA-Z0-9áñ日🚀 .,!?=+(){}|>~-]{0,60}"
+        ) {
+            // No formatting markers, so nothing is dropped: only escapes are
+            // added (and "- " bullets become "• ", which is as long)
+            let rendered = render_markdownv2(&text);
+            prop_assert_eq!(markdownv2_len(&rendered), utf16_len(&text));
+        }
+
+        #[test]
+        fn prop_bold_words_stay_bold(words in prop::collection::vec("[a-zA-Z0-9]{1,8}", 1..5)) {
+            let phrase = words.join(" ");
+            prop_assert_eq!(render_markdownv2(&format!("*{}*", phrase)), format!("*{}*", phrase));
+            prop_assert_eq!(render_markdownv2(&format!("**{}**", phrase)), format!("*{}*", phrase));
+            prop_assert_eq!(render_markdownv2(&format!("_{}_", phrase)), format!("_{}_", phrase));
+        }
+
+        #[test]
+        fn prop_each_line_renders_independently(a in markdown_text(), b in markdown_text()) {
+            let a = a.replace('\n', " ");
+            let b = b.replace('\n', " ");
+            prop_assert_eq!(
+                render_markdownv2(&format!("{}\n{}", a, b)),
+                format!("{}\n{}", render_markdownv2(&a), render_markdownv2(&b))
+            );
+        }
+    }
+}
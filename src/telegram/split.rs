@@ -0,0 +1,356 @@
+//! Delivery of digests longer than one Telegram message.
+//!
+//! A digest is split into ordered parts at section boundaries (blank lines),
+//! then at bullet boundaries (lines), and only as a last resort at spaces
+//! within a bullet. Splitting happens on the model's Markdown, before it is
+//! rendered for the parse mode, so an escape sequence or an entity is never
+//! cut in half, and a cut never falls inside a `[text](url)` link.
+
+use super::format::ParseMode;
+
+/// How to deliver a digest that does not fit in one message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LongMessageStrategy {
+    /// Send several ordered messages ("1/2", "2/2")
+    #[default]
+    Split,
+    /// Shorten the digest with an extra model call, then truncate if needed
+    Condense,
+    /// Cut the digest at the limit
+    Truncate,
+}
+
+impl LongMessageStrategy {
+    pub const ALL: [LongMessageStrategy; 3] = [Self::Split, Self::Condense, Self::Truncate];
+
+    /// Parse "split", "condense" or "truncate" (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Split => "split",
+            Self::Condense => "condense",
+            Self::Truncate => "truncate",
+        }
+    }
+}
+
+/// Split `content` (unescaped Markdown) into parts that each stay within
+/// `limit` once rendered for `mode`, measured as Telegram does (see
+/// [`ParseMode::visible_len`]). Content that fits is returned as a single part.
+pub fn split_content(content: &str, limit: usize, mode: ParseMode) -> Vec<String> {
+    let fits = |text: &str| mode.visible_len(&mode.render(text)) <= limit;
+    if fits(content) {
+        return vec![content.to_string()];
+    }
+
+    // Smallest pieces that fit, each with the separator that precedes it
+    let mut pieces: Vec<(&str, String)> = Vec::new();
+    for (s_idx, section) in content.split("\n\n").enumerate() {
+        let section_sep = if s_idx == 0 { "" } else { "\n\n" };
+        if fits(section) {
+            pieces.push((section_sep, section.to_string()));
+            continue;
+        }
+        for (l_idx, line) in section.split('\n').enumerate() {
+            let line_sep = if l_idx == 0 { section_sep } else { "\n" };
+            if fits(line) {
+                pieces.push((line_sep, line.to_string()));
+                continue;
+            }
+            for (w_idx, words) in split_line(line, &fits).into_iter().enumerate() {
+                pieces.push((if w_idx == 0 { line_sep } else { " " }, words));
+            }
+        }
+    }
+
+    // Greedily pack the pieces into as few parts as possible
+    let mut parts: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for (sep, piece) in pieces {
+        if current.is_empty() {
+            current = piece;
+            continue;
+        }
+        let candidate = format!("{}{}{}", current, sep, piece);
+        if fits(&candidate) {
+            current = candidate;
+        } else {
+            parts.push(std::mem::replace(&mut current, piece));
+        }
+    }
+    if !current.trim().is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Split one over-long line at spaces outside links. A word longer than the
+/// limit is cut between characters, unless it contains a link, which is kept
+/// whole.
+fn split_line(line: &str, fits: &impl Fn(&str) -> bool) -> Vec<String> {
+    let link_regex = regex::Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap();
+    let links: Vec<(usize, usize)> = link_regex
+        .find_iter(line)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    let in_link = |i: usize| links.iter().any(|(start, end)| (*start..*end).contains(&i));
+
+    // Words: runs between spaces that are not inside a link
+    let mut words: Vec<&str> = Vec::new();
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        if c == ' ' && !in_link(i) {
+            words.push(&line[start..i]);
+            start = i + 1;
+        }
+    }
+    words.push(&line[start..]);
+
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in words.into_iter().filter(|w| !w.is_empty()) {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if fits(&candidate) {
+            current = candidate;
+            continue;
+        }
+        if !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if fits(word) || link_regex.is_match(word) {
+            current = word.to_string();
+        } else {
+            let mut pieces = split_chars(word, fits);
+            current = pieces.pop().unwrap_or_default();
+            chunks.extend(pieces);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Cut a word between characters into pieces that fit
+fn split_chars(word: &str, fits: &impl Fn(&str) -> bool) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for c in word.chars() {
+        current.push(c);
+        if !fits(&current) && current.chars().count() > 1 {
+            current.pop();
+            pieces.push(std::mem::take(&mut current));
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::markdownv2_len;
+    use crate::telegram::render_markdownv2;
+
+    fn escaped_len(text: &str) -> usize {
+        markdownv2_len(&render_markdownv2(text))
+    }
+
+    fn sample_digest() -> String {
+        let section = |title: &str, n: usize| {
+            let mut lines = vec![title.to_string()];
+            lines.extend((0..n).map(|i| {
+                format!(
+                    "- {} item {}: something happened. [source post](https://x.com/user/status/{})",
+                    title, i, i
+                )
+            }));
+            lines.join("\n")
+        };
+        [
+            section("🧠 Top takeaways", 5),
+            section("🚀 Releases", 5),
+            section("🔬 Research", 5),
+        ]
+        .join("\n\n")
+    }
+
+    // ==================== Strategy Tests ====================
+
+    #[test]
+    fn test_strategy_parse() {
+        assert_eq!(
+            LongMessageStrategy::parse("split"),
+            Some(LongMessageStrategy::Split)
+        );
+        assert_eq!(
+            LongMessageStrategy::parse(" Condense "),
+            Some(LongMessageStrategy::Condense)
+        );
+        assert_eq!(
+            LongMessageStrategy::parse("TRUNCATE"),
+            Some(LongMessageStrategy::Truncate)
+        );
+        assert_eq!(LongMessageStrategy::parse("shorten"), None);
+        assert_eq!(LongMessageStrategy::default(), LongMessageStrategy::Split);
+    }
+
+    #[test]
+    fn test_strategy_roundtrip() {
+        for strategy in LongMessageStrategy::ALL {
+            assert_eq!(
+                LongMessageStrategy::parse(strategy.as_str()),
+                Some(strategy)
+            );
+        }
+    }
+
+    // ==================== Splitting Tests ====================
+
+    #[test]
+    fn test_content_that_fits_is_one_part() {
+        let content = sample_digest();
+        assert_eq!(
+            split_content(&content, 4096, ParseMode::MarkdownV2),
+            vec![content]
+        );
+    }
+
+    #[test]
+    fn test_splits_at_section_boundaries() {
+        let content = sample_digest();
+        let sections: Vec<&str> = content.split("\n\n").collect();
+        let limit = escaped_len(sections[0]).max(escaped_len(sections[1])) + 10;
+
+        let parts = split_content(&content, limit, ParseMode::MarkdownV2);
+
+        assert_eq!(parts, sections);
+    }
+
+    #[test]
+    fn test_splits_at_bullet_boundaries() {
+        let content = sample_digest();
+        let limit = escaped_len(content.lines().nth(1).unwrap()) * 2;
+
+        let parts = split_content(&content, limit, ParseMode::MarkdownV2);
+
+        assert!(parts.len() > 3);
+        for part in &parts {
+            assert!(escaped_len(part) <= limit, "part too long: {}", part);
+            for line in part.lines() {
+                assert!(
+                    content.lines().any(|l| l == line),
+                    "bullet was cut: {}",
+                    line
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parts_keep_all_content_in_order() {
+        let content = sample_digest();
+        let parts = split_content(&content, 600, ParseMode::MarkdownV2);
+
+        let rejoined: Vec<&str> = parts
+            .iter()
+            .flat_map(|p| p.lines())
+            .filter(|l| !l.is_empty())
+            .collect();
+        let original: Vec<&str> = content.lines().filter(|l| !l.is_empty()).collect();
+        assert_eq!(rejoined, original);
+    }
+
+    #[test]
+    fn test_long_line_never_split_inside_link() {
+        let link = "[a very long link label](https://x.com/user/status/123456789)";
+        let line = format!(
+            "- {} {} {}",
+            "word ".repeat(40).trim(),
+            link,
+            "tail ".repeat(40)
+        );
+        let limit = escaped_len(link) + 20;
+
+        let parts = split_content(&line, limit, ParseMode::MarkdownV2);
+
+        assert!(parts.len() > 1);
+        assert!(parts.iter().any(|p| p.contains(link)));
+        for part in &parts {
+            assert!(escaped_len(part) <= limit);
+            assert_eq!(part.matches('[').count(), part.matches("](").count());
+        }
+    }
+
+    #[test]
+    fn test_escape_sequences_are_never_cut() {
+        // Every other character needs escaping
+        let content = "a.b.c.d.e.f.g.h.i.j.k.l.m.n.o.p";
+        let parts = split_content(content, 10, ParseMode::MarkdownV2);
+
+        assert!(parts.len() > 1);
+        assert_eq!(parts.concat(), content);
+        for part in &parts {
+            let escaped = render_markdownv2(part);
+            assert!(markdownv2_len(&escaped) <= 10);
+            assert!(!escaped.ends_with('\\'));
+        }
+    }
+
+    #[test]
+    fn test_html_parts_measure_visible_length() {
+        // Each "&" is sent as "&amp;" but counts as one character
+        let content = "a&b&c&d&e&f&g&h&i&j&k&l&m&n&o&p";
+        let parts = split_content(content, 10, ParseMode::Html);
+
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts.concat(), content);
+        for part in &parts {
+            assert!(crate::telegram::html_len(&ParseMode::Html.render(part)) <= 10);
+        }
+        assert!(ParseMode::Html.render(&parts[0]).len() > 10);
+    }
+
+    #[test]
+    fn test_multibyte_characters_are_not_cut() {
+        // Each rocket is two UTF-16 units
+        let content = "🚀".repeat(50);
+        let parts = split_content(&content, 20, ParseMode::MarkdownV2);
+
+        assert_eq!(parts.len(), 5);
+        assert_eq!(parts.concat(), content);
+    }
+
+    #[test]
+    fn test_limit_counts_visible_units_not_bytes() {
+        // 200 bytes, but 100 characters once parsed
+        let content = "ñ".repeat(100);
+        assert_eq!(
+            split_content(&content, 100, ParseMode::MarkdownV2),
+            vec![content.clone()]
+        );
+
+        // Link URLs don't count towards the limit
+        let linked = format!(
+            "[{}](https://x.com/{}/status/1)",
+            "a".repeat(90),
+            "b".repeat(50)
+        );
+        assert_eq!(
+            split_content(&linked, 100, ParseMode::MarkdownV2),
+            vec![linked.clone()]
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Digest topics: independent digests served by one deployment.
+//!
+//! Each topic has its own sources (usernames file), prompt persona and audience,
+//! schedule and section taxonomy. Without `DIGESTS_FILE` there is a single topic
+//! (id `default`) built from `USERNAMES_FILE` and `SCHEDULE_TIMES`, which is the
+//! original AI/ML digest. With it, the file is a JSON array:
+//!
+//! ```json
+//! [
+//!   {"id": "default", "name": "AI research", "usernames_file": "data/ai.txt"},
+//!   {"id": "rust", "name": "Rust ecosystem", "usernames_file": "data/rust.txt",
+//!    "schedule_times": ["09:00"], "persona": "a Rust ecosystem news curator",
+//!    "audience": "Rust developers", "sections": ["🚀 Releases", "🧰 Crates and Tools"],
+//!    "prompts_dir": "prompts/rust"}
+//! ]
+//! ```
+//!
+//! Existing subscribers are migrated to the `default` topic, so keep that id for
+//! the original digest.
+
+use crate::i18n::ENGLISH_SECTION_HEADERS;
+use crate::prompts::PromptLibrary;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Id of the topic existing subscribers and summaries belong to
+pub const DEFAULT_DIGEST_ID: &str = "default";
+
+fn default_persona() -> String {
+    "an AI/ML tech news curator".to_string()
+}
+
+fn default_audience() -> String {
+    "AI/ML builders and tech professionals".to_string()
+}
+
+fn default_sections() -> Vec<String> {
+    [
+        ENGLISH_SECTION_HEADERS.releases,
+        ENGLISH_SECTION_HEADERS.research,
+        ENGLISH_SECTION_HEADERS.tools_tutorials,
+        ENGLISH_SECTION_HEADERS.companies_deals,
+        ENGLISH_SECTION_HEADERS.policy_safety,
+        ENGLISH_SECTION_HEADERS.debate_opinions,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// Official org and lab accounts (the examples in `summary_tiers.md`)
+fn default_tier1_accounts() -> Vec<String> {
+    [
+        "OpenAI",
+        "AnthropicAI",
+        "GoogleDeepMind",
+        "PyTorch",
+        "NVIDIAAI",
+        "HuggingFace",
+        "StanfordHAI",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// Technical educators and tool builders (the examples in `summary_tiers.md`)
+fn default_tier2_accounts() -> Vec<String> {
+    ["hwchase17", "jeremyphoward", "fastdotai", "karpathy"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// One independent digest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestTopic {
+    /// Stable identifier used in commands and as the database key
+    pub id: String,
+    /// Display name shown to subscribers
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// File with one Twitter/X username per line (defaults to USERNAMES_FILE)
+    #[serde(default)]
+    pub usernames_file: String,
+    /// Who the model writes as, e.g. "an AI/ML tech news curator"
+    #[serde(default = "default_persona")]
+    pub persona: String,
+    /// Who the digest is for, e.g. "AI/ML builders and tech professionals"
+    #[serde(default = "default_audience")]
+    pub audience: String,
+    /// Send times in Peru time, HH:MM (defaults to SCHEDULE_TIMES)
+    #[serde(default)]
+    pub schedule_times: Vec<String>,
+    /// Allowed topic sections after "🧠 Top takeaways"
+    #[serde(default = "default_sections")]
+    pub sections: Vec<String>,
+    /// Directory with prompt template overrides for this topic only
+    #[serde(default)]
+    pub prompts_dir: Option<String>,
+    /// Authoritative accounts whose topics rank first (embeddings stage)
+    #[serde(default = "default_tier1_accounts")]
+    pub tier1_accounts: Vec<String>,
+    /// Practitioner accounts ranked after tier 1; everyone else is tier 3
+    #[serde(default = "default_tier2_accounts")]
+    pub tier2_accounts: Vec<String>,
+    /// Shared prompts with this topic's overrides applied (resolved at startup)
+    #[serde(skip)]
+    pub prompts: Option<PromptLibrary>,
+}
+
+impl Default for DigestTopic {
+    fn default() -> Self {
+        Self {
+            id: DEFAULT_DIGEST_ID.to_string(),
+            name: "AI & Tech".to_string(),
+            description: "AI/ML news from tech leaders and researchers".to_string(),
+            usernames_file: "data/usernames.txt".to_string(),
+            persona: default_persona(),
+            audience: default_audience(),
+            schedule_times: vec!["08:00".to_string(), "20:00".to_string()],
+            sections: default_sections(),
+            prompts_dir: None,
+            tier1_accounts: default_tier1_accounts(),
+            tier2_accounts: default_tier2_accounts(),
+            prompts: None,
+        }
+    }
+}
+
+impl DigestTopic {
+    /// The single topic used when no DIGESTS_FILE is configured
+    pub fn from_defaults(usernames_file: &str, schedule_times: &[String]) -> Self {
+        Self {
+            usernames_file: usernames_file.to_string(),
+            schedule_times: schedule_times.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// Resolve this topic's prompts: the shared library plus `prompts_dir`
+    pub fn resolve_prompts(&mut self, shared: &PromptLibrary) {
+        let library = shared.clone();
+        self.prompts = Some(match &self.prompts_dir {
+            Some(dir) => library.with_overrides(dir),
+            None => library,
+        });
+    }
+
+    /// Tier of an account (1-3, case-insensitive); unknown authors are tier 3
+    pub fn account_tier(&self, author: Option<&str>) -> u8 {
+        let Some(author) = author else {
+            return 3;
+        };
+        let listed = |accounts: &[String]| accounts.iter().any(|a| a.eq_ignore_ascii_case(author));
+        if listed(&self.tier1_accounts) {
+            1
+        } else if listed(&self.tier2_accounts) {
+            2
+        } else {
+            3
+        }
+    }
+
+    /// Section taxonomy as a Markdown list for the system prompt
+    pub fn section_list(&self) -> String {
+        self.sections
+            .iter()
+            .map(|s| format!("- {}", s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Find a topic by id
+pub fn find_topic<'a>(topics: &'a [DigestTopic], id: &str) -> Option<&'a DigestTopic> {
+    topics.iter().find(|t| t.id == id)
+}
+
+/// Parse and validate a digests file (JSON array of topics).
+///
+/// Topics without `usernames_file` or `schedule_times` inherit the given defaults.
+pub fn parse_topics(
+    json: &str,
+    default_usernames_file: &str,
+    default_schedule_times: &[String],
+) -> Result<Vec<DigestTopic>> {
+    let mut topics: Vec<DigestTopic> =
+        serde_json::from_str(json).context("Digests file must be a JSON array of topics")?;
+
+    if topics.is_empty() {
+        anyhow::bail!("Digests file defines no topics");
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for topic in topics.iter_mut() {
+        let valid_id = !topic.id.is_empty()
+            && topic.id.len() <= 32
+            && topic
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid_id {
+            anyhow::bail!(
+                "Invalid digest id '{}': use 1-32 lowercase letters, digits, '-' or '_'",
+                topic.id
+            );
+        }
+        if topic.name.trim().is_empty() {
+            anyhow::bail!("Digest '{}' has no name", topic.id);
+        }
+        if topic.sections.is_empty() {
+            anyhow::bail!("Digest '{}' has no sections", topic.id);
+        }
+        if topic.usernames_file.is_empty() {
+            topic.usernames_file = default_usernames_file.to_string();
+        }
+        if topic.schedule_times.is_empty() {
+            topic.schedule_times = default_schedule_times.to_vec();
+        }
+        if !seen.insert(topic.id.clone()) {
+            anyhow::bail!("Duplicate digest id '{}'", topic.id);
+        }
+    }
+
+    Ok(topics)
+}
+
+/// Load topics from a digests file
+pub fn load_topics(
+    path: &str,
+    default_usernames_file: &str,
+    default_schedule_times: &[String],
+) -> Result<Vec<DigestTopic>> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read digests file {}", path))?;
+    parse_topics(&json, default_usernames_file, default_schedule_times)
+        .with_context(|| format!("Invalid digests file {}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> Vec<String> {
+        vec!["08:00".to_string(), "20:00".to_string()]
+    }
+
+    // ==================== Parsing Tests ====================
+
+    #[test]
+    fn test_parse_topics_applies_defaults() {
+        let topics = parse_topics(
+            r#"[{"id": "default", "name": "AI"}, {"id": "rust", "name": "Rust",
+                 "usernames_file": "data/rust.txt", "schedule_times": ["09:00"],
+                 "audience": "Rust developers", "sections": ["🦀 Crates"]}]"#,
+            "data/usernames.txt",
+            &defaults(),
+        )
+        .unwrap();
+
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].usernames_file, "data/usernames.txt");
+        assert_eq!(topics[0].schedule_times, defaults());
+        assert_eq!(topics[0].persona, DigestTopic::default().persona);
+        assert_eq!(topics[0].sections.len(), 6);
+
+        assert_eq!(topics[1].usernames_file, "data/rust.txt");
+        assert_eq!(topics[1].schedule_times, vec!["09:00"]);
+        assert_eq!(topics[1].audience, "Rust developers");
+        assert_eq!(topics[1].sections, vec!["🦀 Crates"]);
+        assert_eq!(find_topic(&topics, "rust").unwrap().name, "Rust");
+        assert!(find_topic(&topics, "go").is_none());
+    }
+
+    #[test]
+    fn test_parse_topics_rejects_invalid_files() {
+        let cases = [
+            ("[]", "no topics"),
+            ("{}", "JSON array"),
+            (r#"[{"id": "AI", "name": "x"}]"#, "Invalid digest id"),
+            (r#"[{"id": "", "name": "x"}]"#, "Invalid digest id"),
+            (r#"[{"id": "ai", "name": " "}]"#, "no name"),
+            (
+                r#"[{"id": "ai", "name": "x", "sections": []}]"#,
+                "no sections",
+            ),
+            (
+                r#"[{"id": "ai", "name": "x"}, {"id": "ai", "name": "y"}]"#,
+                "Duplicate digest id",
+            ),
+        ];
+        for (json, expected) in cases {
+            let err = parse_topics(json, "u.txt", &defaults()).unwrap_err();
+            assert!(
+                format!("{:#}", err).contains(expected),
+                "{}: {:#}",
+                json,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_example_digests_file_is_valid() {
+        let topics = parse_topics(
+            include_str!("../data/digests.example.json"),
+            "data/usernames.txt",
+            &defaults(),
+        )
+        .unwrap();
+        assert_eq!(topics[0].id, DEFAULT_DIGEST_ID);
+        assert!(topics.len() > 1);
+    }
+
+    #[test]
+    fn test_account_tier() {
+        let topic = DigestTopic::default();
+        assert_eq!(topic.account_tier(Some("openai")), 1);
+        assert_eq!(topic.account_tier(Some("karpathy")), 2);
+        assert_eq!(topic.account_tier(Some("someone")), 3);
+        assert_eq!(topic.account_tier(None), 3);
+
+        let rust = parse_topics(
+            r#"[{"id": "rust", "name": "Rust", "tier1_accounts": ["rustlang"], "tier2_accounts": []}]"#,
+            "u.txt",
+            &defaults(),
+        )
+        .unwrap()
+        .remove(0);
+        assert_eq!(rust.account_tier(Some("RustLang")), 1);
+        assert_eq!(rust.account_tier(Some("karpathy")), 3);
+    }
+
+    #[test]
+    fn test_section_list() {
+        let topic = DigestTopic {
+            sections: vec!["🚀 Releases".to_string(), "🦀 Crates".to_string()],
+            ..DigestTopic::default()
+        };
+        assert_eq!(topic.section_list(), "- 🚀 Releases\n- 🦀 Crates");
+    }
+}